@@ -1,5 +1,7 @@
 fn main() {
-    if cfg!(debug_assertions) {
+    // the manifest only means something to windows, cross and linux builds skip it
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if cfg!(debug_assertions) && target_os == "windows" {
         let mut res = winres::WindowsResource::new();
         res.set_manifest(r#"
             <assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
@@ -1,26 +1,48 @@
-use std::collections::{VecDeque, HashMap};
-use std::ffi::CString;
-use std::time::Instant;
-use egui::epaint::Hsva;
-use egui::scroll_area::ScrollBarVisibility;
-use egui::{SidePanel, RichText, Color32, Layout, Align, plot, ScrollArea, Grid, Label, Sense, Ui, Rect, Pos2};
-use egui::plot::{Line, Legend, PlotBounds, Plot, Corner};
-use itertools::Itertools;
-use sysinfo::{NetworkExt, NetworksExt, System, SystemExt, CpuExt, MacAddr, Cpu, DiskExt, RefreshKind, DiskKind, Pid, ProcessExt, Process, DiskUsage};
-use winapi::um::ioapiset::DeviceIoControl;
-use winapi::um::winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
+use std::time::SystemTime;
 use core::time::Duration;
-use std::str;
-use std::ptr;
-use winapi::um::handleapi::CloseHandle;
-use winapi::um::fileapi::{CreateFileA, OPEN_EXISTING};
-use winapi::um::winioctl::{IOCTL_DISK_PERFORMANCE, DISK_PERFORMANCE};
-use winapi::shared::minwindef::{DWORD, FALSE};
-use winapi::shared::ntdef::HANDLE;
-use rand::prelude::*;
+use egui::scroll_area::ScrollBarVisibility;
+use egui::{SidePanel, RichText, Color32, Layout, Align, plot, ScrollArea, Grid, Label, Sense, Ui, Rect, Vec2, pos2, vec2};
+use egui::plot::{Line, Legend, PlotBounds, Plot, Corner};
+use itertools::Itertools;
+use sysinfo::{System, SystemExt, CpuExt, Pid};
+use crate::config::{Config, ConfigWatcher, ProbeConfig, ThresholdsConfig};
+use crate::export;
+use crate::probe::Prober;
+use crate::error::Severity;
+use crate::sampler::{Sampler, SamplerCommand};
+use crate::schedule::OpenViews;
+use crate::snapshot::{Snapshot, ProcessInformations, ProcessDetails, NetworkInformations, BlockDevice, DiskInformations, DiskIoStats, InterfaceDetails, ConnectionInformations, ProcessMemory, OpenFiles, OpenFileKind, IoPriority, IoPriorityClass, ProcessIo, ThreadInformations, Data, CpuData, CpuBreakdown, MemoryBreakdown, PressureInformations, PressureLine, SensorKind};
+
+/// Attempts the loss line of a probe is averaged over.
+const PROBE_LOSS_WINDOW: usize = 10;
+
+/// Colors of the stacked cpu breakdown, in the order of `CpuBreakdown::stacked`.
+const CPU_BREAKDOWN_COLORS: [Color32; 8] = [
+    Color32::from_rgb(70, 130, 180),
+    Color32::from_rgb(100, 160, 100),
+    Color32::from_rgb(210, 151, 49),
+    Color32::from_rgb(160, 90, 160),
+    Color32::from_rgb(190, 120, 190),
+    Color32::from_rgb(200, 70, 70),
+    Color32::from_rgb(240, 200, 60),
+    Color32::from_rgb(90, 190, 190),
+];
+
+/// Colors of the stacked memory breakdown, in the order of `MemoryBreakdown::stacked`.
+const MEMORY_BREAKDOWN_COLORS: [Color32; 7] = [
+    Color32::from_rgb(200, 70, 70),
+    Color32::from_rgb(160, 90, 160),
+    Color32::from_rgb(210, 151, 49),
+    Color32::from_rgb(180, 160, 90),
+    Color32::from_rgb(100, 160, 100),
+    Color32::from_rgb(70, 130, 180),
+    Color32::from_rgb(90, 190, 190),
+];
 
 
 //#[serde(default)] // if we add new fields, give them default values when deserializing old state
@@ -28,29 +50,47 @@ pub struct ProcessManagerApp {
     cpu_informations: CpuInformations,
     system_informations: SystemInformations,
     memory_informations: MemoryInformations,
-    cpus_columns: usize,
-    process_manager_mutex_data: Arc<Mutex<ProcessManagerAppMutexData>>,
+    view_state: ViewState,
+    snapshot: Arc<Snapshot>,
+    snapshots: Receiver<Arc<Snapshot>>,
+    sampler_commands: Sender<SamplerCommand>,
+    sampler: Option<(Sampler, Sender<Arc<Snapshot>>, Receiver<SamplerCommand>)>,
+    probe_configs: Sender<Vec<ProbeConfig>>,
+    prober: Option<(Prober, Receiver<Vec<ProbeConfig>>)>,
 }
 
-pub struct ProcessManagerAppMutexData{
-    total_cpu_usage: u64,
-    memory_usage: u64,
-    swap_usage: u64,
-    network_informations: Vec<NetworkInformations>,
-    cpu_performance_data_points: Data<f32>,
-    cpus_performance_data_points: Vec<CpuData>,
-    memory_usage_data_points: Data<f32>,
-    disks_informations: Vec<DiskInformations>,
-    swap_usage_data_points: Data<f32>,
-    network_y_plot_bound: f64,
-    process_informations: HashMap<Pid, ProcessInformations>,
+/// What the user picked in the UI. Owned by the UI thread, the sampler never sees it.
+struct ViewState {
+    cpus_columns: usize,
+    cpus_on_plot: HashSet<String>,
+    cpus_heatmap: bool,
+    cpu_breakdown_on_plot: bool,
+    networks_hidden_on_plot: HashSet<String>,
+    /// Group key of `snapshot.process_informations` (the parent pid) shown above the list.
     clicked_process: Option<Pid>,
+    /// A process of the clicked group marked in its rows, e.g. the owner of a clicked connection.
+    highlighted_process: Option<Pid>,
     processes_sort_option: ProcessesSortOption,
-    system: System
+    inspected_process: Option<Pid>,
+    process_details_tab: ProcessDetailsTab,
+    mappings_sorted_by_growth: bool,
+    open_file_query: String,
+    connections_filter: String,
+    process_io_only_active: bool,
+    /// Last reported to the sampler.
+    open_views: OpenViews,
+    /// The I/O priority being edited in the details window, for the process it was started on.
+    io_priority_edit: Option<(Pid, IoPriority)>,
+    /// Where the last exported snapshot went, or why it failed.
+    last_export: Option<Result<PathBuf, String>>,
 }
 
 impl ProcessManagerApp {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+        let (config, config_error) = match Config::load() {
+            Ok(config) => (config, None),
+            Err(e) => (Config::default(), Some(e.to_string())),
+        };
         let mut sys = System::new_all(); 
         sys.refresh_all();
         let cpu_brand = sys.global_cpu_info().brand().to_string();
@@ -76,103 +116,44 @@ impl ProcessManagerApp {
             host_name,
             system_version_full_name,
         };
-        let disks_informations = sys.disks().iter().map(|x|{
-            let name = if format!("{:?}", x.name()).replace("\"", "") != "" {
-                format!("{:?}", x.name()).replace("\"", "")
-            } else {
-                String::from("None")
-            };
-            let kind = match x.kind() {
-                DiskKind::SSD => String::from("SSD"),
-                DiskKind::HDD => String::from("HDD"),
-                DiskKind::Unknown(_) => String::from("UNKNOWN")
-            };
-            let useed_space_tuple = ProcessManagerApp::bytes_to_gb_or_tb_tuple(x.total_space() - x.available_space());
-            let total_space_tuple = ProcessManagerApp::bytes_to_gb_or_tb_tuple(x.total_space());
-            let file_system = match std::str::from_utf8(x.file_system()) {
-                Ok(value) => {
-                    value.to_string()
-                }
-                Err(_) => {
-                    panic!("nie panikuj!")
-                }
-            };
-            let is_removable = if x.is_removable() { String::from("yes") } else { String::from("no") };
-            let disk_fields = vec![
-                name,
-                format!("{:?}", x.mount_point()).replace("\"", ""),
-                format!("{:.2} {}", useed_space_tuple.0, useed_space_tuple.1),
-                format!("{:.2} {}", total_space_tuple.0, total_space_tuple.1),
-                format!("{}", kind),
-                file_system,
-                is_removable,
-            ];
-            let longest_length = disk_fields.iter().map(|s| s.len()).max().unwrap_or(0);
-            let adjusted_disk_fields: Vec<String> = disk_fields
-                .iter()
-                .map(|s| {
-                    let padding = " ".repeat(longest_length.saturating_sub(s.len()));
-                    format!("{}{}", s, padding)
-                }).collect();
-
-            DiskInformations{
-                name: adjusted_disk_fields[0].to_string(),
-                mount_point: adjusted_disk_fields[1].to_string(),
-                used_space: adjusted_disk_fields[2].to_string(),
-                total_space: adjusted_disk_fields[3].to_string(),
-                kind: adjusted_disk_fields[4].to_string(),
-                file_system: adjusted_disk_fields[5].to_string(),
-                is_removable: adjusted_disk_fields[6].to_string(),
-                last_performance: None,
-                plot_points: Data::new(20),
-                y_max_bound: 450.0,
-            }
-        }).collect();
         let memory_informations = MemoryInformations{
             total_memory: sys.total_memory(),
-            total_swap: sys.total_swap(),
         };
-        let network_informations = sys.networks().iter()
-            .enumerate()
-            .map(|(i, x)|{
-                NetworkInformations {
-                    number: i + 1,
-                    interface_name: x.0.to_string(),
-                    mac_address: x.1.mac_address(),
-                    is_display_on_plot: true,
-                    network_display: None,
-                    total_errors_on_received: 0,
-                    total_errors_on_transmitted: 0,
-                }
-            }).collect();
 
-        let cpu_performance_data_points = Data::new(20);
-        let memory_usage_data_points = Data::new(20);
-        let swap_usage_data_points = Data::new(20);
-        let cpus_performance_data_points: Vec<CpuData> = CpuData::new(sys.cpus(), 1);
-
-        let process_manager_mutex_data = Arc::new(Mutex::new(ProcessManagerAppMutexData{
-            total_cpu_usage: 0,
-            memory_usage: 0,
-            swap_usage: 0,
-            cpu_performance_data_points,
-            memory_usage_data_points,
-            swap_usage_data_points,
-            cpus_performance_data_points,
-            network_informations,
-            disks_informations,
-            network_y_plot_bound: 100.0,
-            process_informations: HashMap::new(),
-            clicked_process: None,
-            processes_sort_option: ProcessesSortOption::Memory,
-            system: sys,
-        }));
+        let prober = Prober::new(config.probes.clone());
+        let (probe_configs, probe_config_receiver) = mpsc::channel();
+        let sampler = Sampler::new(sys, config, config_error);
+        let (snapshot_sender, snapshots) = mpsc::channel();
+        let (sampler_commands, command_receiver) = mpsc::channel();
 
         Self {
-            process_manager_mutex_data,
+            snapshot: sampler.snapshot(),
+            snapshots,
+            sampler_commands,
+            sampler: Some((sampler, snapshot_sender, command_receiver)),
+            probe_configs,
+            prober: Some((prober, probe_config_receiver)),
             system_informations,
             memory_informations,
-            cpus_columns: 4,
+            view_state: ViewState {
+                cpus_columns: 4,
+                cpus_on_plot: HashSet::new(),
+                cpus_heatmap: false,
+                cpu_breakdown_on_plot: true,
+                networks_hidden_on_plot: HashSet::new(),
+                clicked_process: None,
+                highlighted_process: None,
+                processes_sort_option: ProcessesSortOption::Memory,
+                inspected_process: None,
+                process_details_tab: ProcessDetailsTab::Memory,
+                mappings_sorted_by_growth: false,
+                open_file_query: String::new(),
+                connections_filter: String::new(),
+                process_io_only_active: true,
+                open_views: OpenViews::default(),
+                io_priority_edit: None,
+                last_export: None,
+            },
             cpu_informations: CpuInformations { 
                 cpu_brand,
                 kernel_version,
@@ -181,174 +162,47 @@ impl ProcessManagerApp {
         }
     }
 
-    pub fn start_updating_system_info(&self)
+    pub fn start_updating_system_info(&mut self)
     {
-        let arc_process_manager_mutex_data = Arc::clone(&self.process_manager_mutex_data);
-
-        let mut last_measurement_time = Instant::now();
-
-        thread::spawn(move || {
-            loop {
-                {
-                    let process_manager_mutex_data = &mut *arc_process_manager_mutex_data.lock().unwrap();
-                    let sys = &mut process_manager_mutex_data.system;
-
-                    sys.refresh_specifics(RefreshKind::everything()
-                        .without_components()
-                        .without_components_list()
-                        .without_users_list());
-
-                    let processor = sys.global_cpu_info().cpu_usage();
-                    let memory = (sys.used_memory() as f64 / sys.total_memory() as f64) * 100.0;
-                    let swap =  (sys.used_swap() as f64 / sys.total_swap() as f64) * 100.0;
-
-                    sys.cpus().iter().for_each(|x|{
-                        let cpu_data = process_manager_mutex_data.cpus_performance_data_points.iter_mut().find(|y| y.name == x.name());
-                        match cpu_data {
-                            Some(cpu) => {
-                                cpu.usage = x.cpu_usage();
-                                if cpu.is_display_on_plot {
-                                    match &mut cpu.plot_points {
-                                        Some(plot_points) => {
-                                            plot_points.push(cpu.usage);
-                                        }
-                                        None => {
-                                            let mut plot_points: Data<f32> = Data::new(20);
-                                            plot_points.push(cpu.usage);
-                                            cpu.plot_points = Some(plot_points);
-                                        }
-                                    }
-                                }
-                                else {
-                                    cpu.plot_points = None;
-                                }
-                            }
-                            None => {
-                                panic!("nie panikuj!");
-                            }
-                        }
-                    });
-
-                    process_manager_mutex_data.process_informations = sys.processes()
-                        .iter()
-                        .into_group_map_by(|x| match x.1.parent() {
-                            Some(value) => { value }
-                            None => Pid::from(rand::thread_rng().gen_range(10_000..100_000))
-                        }).into_iter().map(|x|{
-                            let inner_vec: Vec<SecificProcess> = x.1.iter().map(|col|{
-                                SecificProcess { 
-                                    pid: *col.0, 
-                                    name: col.1.name().to_string(), 
-                                    cpu: (col.1.cpu_usage() / sys.physical_core_count().unwrap() as f32), 
-                                    memory: col.1.memory() as f32 / 1_048_576.0, 
-                                    disk: (col.1.disk_usage().read_bytes + col.1.disk_usage().written_bytes) as f32 }
-                            }).collect();
-
-                            let cpu: f32 = inner_vec.iter().map(|y| y.cpu).sum();
-                            let name = inner_vec.iter().max_by_key(|x| x.memory as i32).unwrap().name.clone();
- 
-                            (x.0, ProcessInformations {
-                                pid: x.0,
-                                name: name,
-                                cpu: cpu,
-                                memory: inner_vec.iter().map(|y| y.memory as f32).sum(),
-                                disk: inner_vec.iter().map(|y| y.disk).sum(),
-                                child_processes: inner_vec,
-                            })
-                        }).collect();
-
-                    sys.disks().iter().for_each(|x|{
-                        let disk = process_manager_mutex_data.disks_informations.iter_mut().find(|disk| 
-                            disk.mount_point.trim() == format!("{:?}", x.mount_point()).replace("\"", "").trim().to_string()).unwrap();
-                        
-                        set_disk_transfer(disk, &mut last_measurement_time);
-                        let mut disk_y_max_bound = 450.0;
-                        let bound = disk.plot_points.data_iter().max_by(|a, b| a.partial_cmp(b).unwrap()); 
-                        match bound {
-                            Some(value) => {
-                                if *value > disk_y_max_bound{
-                                    disk_y_max_bound = *value
-                                }
-                            }
-                            None => {
-                            }
-                        }
-                        disk.y_max_bound = disk_y_max_bound;
-                    });
+        if let Some((sampler, snapshot_sender, command_receiver)) = self.sampler.take() {
+            sampler.spawn(snapshot_sender, command_receiver);
+        }
+    }
 
-                    process_manager_mutex_data.total_cpu_usage = processor.round() as u64;
-                    process_manager_mutex_data.memory_usage = sys.used_memory();
-                    process_manager_mutex_data.swap_usage = sys.used_swap();
-                    process_manager_mutex_data.cpu_performance_data_points.push(processor);
-                    process_manager_mutex_data.memory_usage_data_points.push(memory as f32);
-                    process_manager_mutex_data.swap_usage_data_points.push(swap as f32);
-                    process_manager_mutex_data.network_informations.iter_mut().for_each(|x: &mut NetworkInformations| {
-                        let net_data = sys.networks().iter()
-                            .find(|y| y.1.mac_address().eq(&x.mac_address));
-                        match net_data {
-                            Some(data) => {
-                                x.total_errors_on_received = data.1.total_errors_on_received();
-                                x.total_errors_on_transmitted = data.1.total_errors_on_transmitted();
-                                if x.is_display_on_plot {
-                                let received = data.1.received();
-                                let transmitted = data.1.transmitted();
-                                    match &mut x.network_display {
-                                        Some(value) => {
-                                            value.received_plot_points.push(received);
-                                            value.transmitted_plot_points.push(transmitted);
-                                        }
-                                        None => {
-                                            let mut received_points = Data::new(20);
-                                            let mut transmitted_points = Data::new(20);
-                                            received_points.push(received);
-                                            transmitted_points.push(transmitted);
-                                            let network_display = NetworkDisplay {
-                                                received_plot_points: received_points,
-                                                transmitted_plot_points: transmitted_points
-                                            };
+    /// Probes run on their own thread and report to the sampler.
+    pub fn start_probing(&mut self)
+    {
+        if let Some((prober, probe_configs)) = self.prober.take() {
+            prober.spawn(probe_configs, self.sampler_commands.clone());
+        }
+    }
 
-                                            x.network_display = Some(network_display);
-                                        }
-                                    }
-                                }
-                                else {
-                                    x.network_display = None;
-                                }
-                            }
-                            None => {
-                                panic!("nie panikuj!");
-                            }
-                        }
-                    });
+    /// Polls the config file and hands it to the sampler and the probe thread on change. A broken file keeps the last good config and reports the error.
+    pub fn start_watching_config(&self)
+    {
+        let sampler_commands = self.sampler_commands.clone();
+        let probe_configs = self.probe_configs.clone();
 
+        let mut config_watcher = ConfigWatcher::new();
 
-                    let mut net_y_bound: u64 = 100;
-    
-                    for info in &process_manager_mutex_data.network_informations {
-                        if let Some(data) = &info.network_display {
-                            if info.is_display_on_plot {
-                                for value in data.received_plot_points.data_iter() {
-                                    if *value > net_y_bound {
-                                        net_y_bound = *value;
-                                    }
-                                }
-                                for value in data.transmitted_plot_points.data_iter() {
-                                    if *value > net_y_bound {
-                                        net_y_bound = *value;
-                                    }
-                                }
-                            }
-                        }
+        thread::spawn(move || {
+            loop {
+                if let Some(result) = config_watcher.poll() {
+                    if let Ok(config) = &result {
+                        let _ = probe_configs.send(config.probes.clone());
+                    }
+                    let command = SamplerCommand::ApplyConfig(result.map(Box::new).map_err(|e| e.to_string()));
+                    if sampler_commands.send(command).is_err() {
+                        return;
                     }
-
-                    process_manager_mutex_data.network_y_plot_bound = net_y_bound as f64;              
                 }
                 thread::sleep(Duration::from_secs(1));
             }
         });
     }
 
-    fn bytes_to_gb_or_tb_tuple(bytes: u64) -> (f64, String) {
+
+    pub(crate) fn bytes_to_gb_or_tb_tuple(bytes: u64) -> (f64, String) {
         const GB: u64 = 1_073_741_824; // 1 GB = 2^30 bytes
         const TB: u64 = 1_099_511_627_776; // 1 TB = 2^40 bytes
     
@@ -366,444 +220,852 @@ impl ProcessManagerApp {
     }
 }
 
-fn set_disk_transfer(disk: &mut DiskInformations, last_measurement_time: &mut Instant) {
-    let disk_name = disk.mount_point.replace("\\", "");
-    if let Some(current_performance) = get_disk_performance(&disk_name) {
-        if let Some(last) = disk.last_performance {
-            unsafe {
-                let read_diff = current_performance.BytesRead.QuadPart() - last.BytesRead.QuadPart();
-                let write_diff = current_performance.BytesWritten.QuadPart() - last.BytesWritten.QuadPart();
-                
-                let total_diff = read_diff + write_diff;
-                
-                let elapsed_time = last_measurement_time.elapsed().as_secs_f64();
-                
-                // transfer spped/rate (KB/s)
-                let transfer_rate_kb_per_sec = (total_diff as f64 / 1024.0) / elapsed_time;
-                disk.plot_points.push(transfer_rate_kb_per_sec as f32);
-            }
-        }
-        disk.last_performance = Some(current_performance);
-
-        *last_measurement_time = Instant::now();
-    }
-}
-
-// FROM C++ https://stackoverflow.com/a/30451751 MY CREATIVE INVERTION AND TRIAL AND ERROR METHOD (It works) and winapi docs and frineds help
-fn get_disk_performance(disk_name: &str) -> Option<DISK_PERFORMANCE> {
-    unsafe {
-        let dev = CreateFileA(
-            CString::new(format!("\\\\.\\{}", disk_name)).unwrap().as_ptr(),
-            winapi::um::winnt::FILE_READ_ATTRIBUTES,
-            FILE_SHARE_READ | FILE_SHARE_WRITE,
-            ptr::null_mut(),
-            OPEN_EXISTING,
-            0,
-            ptr::null_mut(),
-        );
-
-        if HANDLE::is_null(dev) {
-            eprintln!("Error while opening disk");
-            return None;
-        }
-
-        let mut disk_info: DISK_PERFORMANCE = std::mem::zeroed();
-        let mut bytes: DWORD = 0;
-
-        //https://learn.microsoft.com/en-us/windows/win32/fileio/disk-management-control-codes
-        //https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/System/Ioctl/struct.DISK_PERFORMANCE.html
-        if DeviceIoControl(
-            dev,
-            IOCTL_DISK_PERFORMANCE,
-            ptr::null_mut(),
-            0,
-            &mut disk_info as *mut _ as *mut winapi::ctypes::c_void,
-            std::mem::size_of::<DISK_PERFORMANCE>() as DWORD,
-            &mut bytes,
-            ptr::null_mut(),
-        ) == FALSE
-        {
-            eprintln!("Error in DeviceIoControl");
-            CloseHandle(dev);
-            return None;
-        }
-
-        CloseHandle(dev);
-
-        Some(disk_info)
-    }
-}
-
 impl eframe::App for ProcessManagerApp {
-    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+    fn save(&mut self, _storage: &mut dyn eframe::Storage) {
     }
 
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         
         let window_size = _frame.info().window_info.size;
 
-        let mutex_data_arc = Arc::clone(&self.process_manager_mutex_data);
-        let mutex_data = &mut *mutex_data_arc.lock().unwrap();
+        if let Some(snapshot) = self.snapshots.try_iter().last() {
+            self.snapshot = snapshot;
+        }
+        let snapshot = Arc::clone(&self.snapshot);
+        let config = &snapshot.config;
+        let colors = &config.colors;
+        let history_length = config.sampling.history_length as f64;
 
         let cpu_points: Vec<[f64; 2]> = {
-            mutex_data.cpu_performance_data_points.data_iter().enumerate().map(|(index, &i)| {
+            snapshot.cpu_performance_data_points.data_iter().enumerate().map(|(index, &i)| {
                 [index as f64, f64::from(i)]
             }).collect()
         };
 
         let memory_points: Vec<[f64; 2]> = {
-            mutex_data.memory_usage_data_points.data_iter().enumerate().map(|(index, &i)| {
+            snapshot.memory_usage_data_points.data_iter().enumerate().map(|(index, &i)| {
                 [index as f64, f64::from(i)]
             }).collect()
         };
 
         let swap_points: Vec<[f64; 2]> = {
-            mutex_data.swap_usage_data_points.data_iter().enumerate().map(|(index, &i)| {
+            snapshot.swap_usage_data_points.data_iter().enumerate().map(|(index, &i)| {
                 [index as f64, f64::from(i)]
             }).collect()
         };
         
-        let plot_bounds = PlotBounds::from_min_max([0.0, 0.0], [20.0, 100.0]);
-        let mut max_y_network_plot_bound = mutex_data.network_y_plot_bound;
+        let plot_bounds = PlotBounds::from_min_max([0.0, 0.0], [history_length, 100.0]);
+        let mut max_y_network_plot_bound = snapshot.network_informations.iter()
+            .filter(|net| !self.view_state.networks_hidden_on_plot.contains(&net.interface_name))
+            .flat_map(|net| net.network_display.received_plot_points.data_iter().chain(net.network_display.transmitted_plot_points.data_iter()))
+            .copied()
+            .fold(config.plots.network_y_min_bound as f64, f64::max);
         max_y_network_plot_bound += max_y_network_plot_bound * 0.19;
 
-        SidePanel::left("left_panel1").resizable(false).show(ctx, |ui|{
-            ui.set_max_width(0.32 * window_size.x);
-            ui.set_max_height(0.32 * window_size.y);
-            
-            let plot = Plot::new("CPU")
-                .show_axes([false, true])
-                .height(0.32 * window_size.y)
-                .width(0.32 * window_size.x)
-                .allow_scroll(false)
-                .allow_drag(false)
-                .legend(Legend::default().position(Corner::LeftTop).background_alpha(0.0));
-
-            let cpu_line = Line::name(Line::new(cpu_points), "cpu %");
-
-            plot.show(ui, |plot_ui|{
-                plot_ui.line(cpu_line);
-
-                mutex_data.cpus_performance_data_points.iter().for_each(|x|{
-                    match &x.plot_points {
-                        Some(points) => {
-                            let inner_points: Vec<[f64; 2]> = points.data_iter().enumerate().map(|(index, &i)| {
-                                [index as f64, f64::from(i)]
-                            }).collect();
-                            plot_ui.line(Line::new(inner_points).name(&x.name).color(x.color));
-                        }
-                        None => {         
-                        }
-                    };
-                });
-
-                plot_ui.set_plot_bounds(plot_bounds);
+        if let Some(config_error) = &snapshot.config_error {
+            egui::TopBottomPanel::bottom("config_error").show(ctx, |ui|{
+                ui.colored_label(colors.alert.0, format!("Config not applied: {}", config_error));
             });
-        
-            ui.horizontal(|inner_ui|{
-                inner_ui.vertical(|inner_ui|{
-                    inner_ui.horizontal(|inner_ui|{
-                        inner_ui.label(RichText::new("CPU :"));
-                        inner_ui.colored_label(Color32::RED, RichText::new(format!("{}%", mutex_data.total_cpu_usage)));
+        }
+
+        if !snapshot.error_log.is_empty() {
+            egui::TopBottomPanel::bottom("error_log").show(ctx, |ui|{
+                ui.horizontal(|inner_ui|{
+                    inner_ui.colored_label(colors.alert.0, format!("{} recent collection problems", snapshot.error_log.len()));
+                    inner_ui.small_button("clear").clicked().then(||{
+                        let _ = self.sampler_commands.send(SamplerCommand::ClearErrors);
                     });
-                    inner_ui.menu_button("plot", |inner_ui|{
-                        mutex_data.cpus_performance_data_points.iter_mut().for_each(|cpu|{
-                            inner_ui.checkbox(&mut cpu.is_display_on_plot, &cpu.name);
+                });
+                egui::CollapsingHeader::new("details").id_source("error_log_details").show(ui, |inner_ui|{
+                    ScrollArea::vertical().max_height(120.0).show(inner_ui, |inner_ui|{
+                        let now = SystemTime::now();
+                        snapshot.error_log.entries().rev().for_each(|entry|{
+                            let color = match entry.error.severity() {
+                                Severity::Warning => colors.accent.0,
+                                Severity::Error => colors.alert.0,
+                            };
+                            let seconds_ago = now.duration_since(entry.last_seen).unwrap_or_default().as_secs();
+                            let repeated = if entry.count > 1 { format!(" (x{})", entry.count) } else { String::new() };
+                            inner_ui.colored_label(color, format!("{}s ago: {}{}", seconds_ago, entry.error, repeated));
                         });
                     });
-                    inner_ui.add_space(3.0);
-                    inner_ui.add(egui::DragValue::new(&mut self.cpus_columns).speed(0.03).clamp_range(1.0..=4.0).suffix(" columns"));
-                    inner_ui.set_min_width(63.0)
                 });
-                inner_ui.separator();
-                inner_ui.vertical(|inner_ui|{
-                    inner_ui.add_space(3.5);
-                    mutex_data.cpus_performance_data_points.chunks(self.cpus_columns).for_each(|x|{
-                        inner_ui.horizontal(|inner_ui|{
-                            for ele in x {
-                                inner_ui.vertical(|inner_ui|{
-                                    inner_ui.set_min_width(70.0);
-                                    inner_ui.label(format!("{}: {}%",ele.name, ele.usage.round() as i32));
-                                    inner_ui.add_space(0.7);
-                                });
+            });
+        }
+
+        if config.panels.connections {
+            egui::TopBottomPanel::bottom("CONNECTIONS").resizable(true).default_height(0.2 * window_size.y).show(ctx, |ui|{
+                ui.horizontal(|inner_ui|{
+                    inner_ui.label(RichText::new("CONNECTIONS").color(colors.accent.0));
+                    inner_ui.text_edit_singleline(&mut self.view_state.connections_filter)
+                        .on_hover_text("port or state, e.g. 443 or LISTEN");
+                });
+                let connections = match &snapshot.connections {
+                    Ok(value) => value,
+                    Err(e) => {
+                        ui.label(RichText::new(e).weak());
+                        return;
+                    }
+                };
+                let filter = self.view_state.connections_filter.trim();
+                let filtered = connections.iter()
+                    .filter(|x| connection_matches(x, filter))
+                    .sorted_by(|a, b| a.process_name.cmp(&b.process_name).then(a.local.port().cmp(&b.local.port())));
+
+                ScrollArea::vertical().id_source("connections").auto_shrink([false; 2]).show(ui, |inner_ui|{
+                    Grid::new("connections_grid").num_columns(7).striped(true).spacing([17.0, 2.0]).show(inner_ui, |inner_ui|{
+                        ["protocol", "local", "remote", "state", "process", "send queue", "receive queue"].iter().for_each(|x|{
+                            inner_ui.label(RichText::new(*x).weak());
+                        });
+                        inner_ui.end_row();
+                        filtered.for_each(|connection|{
+                            let version = if connection.local.is_ipv4() { "4" } else { "6" };
+                            inner_ui.label(format!("{}{}", connection.protocol, version));
+                            inner_ui.label(connection.local.to_string());
+                            inner_ui.label(connection.remote.to_string());
+                            inner_ui.label(connection.state);
+                            match connection.pid {
+                                Some(pid) => {
+                                    inner_ui.add(Label::new(format!("{} ({})", connection.process_name, pid)).sense(Sense::click()))
+                                        .on_hover_text("show in processes")
+                                        .clicked()
+                                        .then(||{
+                                            if let Some(group) = process_group(&snapshot.process_informations, pid) {
+                                                self.view_state.clicked_process = Some(group);
+                                                self.view_state.highlighted_process = Some(pid);
+                                            }
+                                        });
+                                }
+                                None => {
+                                    inner_ui.label(RichText::new("unknown").weak());
+                                }
                             }
+                            inner_ui.label(connection.send_queue.to_string());
+                            inner_ui.label(connection.receive_queue.to_string());
+                            inner_ui.end_row();
                         });
-                    })
-                })
+                    });
+                });
             });
+        }
 
-            ui.separator();
-
-            ui.label(RichText::new(&self.cpu_informations.cpu_brand).heading().color(Color32::from_rgb(210, 151, 49)));
-            match &self.cpu_informations.os_version {
-                Some(value) => ui.label(RichText::new(format!("os version: {}", value))),
-                _ => { return; }
-            };
-            match &self.cpu_informations.kernel_version {
-                Some(value) => ui.label(RichText::new(format!("kernel version: {}", value))),
-                _ => { return; }
-            };
+        if config.panels.cpu || config.panels.network {
+            SidePanel::left("left_panel1").resizable(false).show(ctx, |ui|{
+                ui.set_max_width(0.32 * window_size.x);
+                ui.set_max_height(0.32 * window_size.y);
+            
+                if config.panels.cpu {
+                    let plot = Plot::new("CPU")
+                        .show_axes([false, true])
+                        .height(0.32 * window_size.y)
+                        .width(0.32 * window_size.x)
+                        .allow_scroll(false)
+                        .allow_drag(false)
+                        .legend(Legend::default().position(Corner::LeftTop).background_alpha(0.0));
+
+                    // the breakdown needs two samples, while the history fills it is one short and ends with the "cpu %" line
+                    let breakdown_offset = cpu_points.len().saturating_sub(snapshot.cpu_breakdown_data_points.data_iter().count());
+                    let cpu_line = Line::name(Line::new(cpu_points), "cpu %");
+
+                    let show_breakdown = self.view_state.cpu_breakdown_on_plot && snapshot.cpu_breakdown_data_points.data_iter().next().is_some();
+                    plot.show(ui, |plot_ui|{
+                        if show_breakdown {
+                            let names = CpuBreakdown::default().stacked().map(|x| x.0);
+                            let samples = snapshot.cpu_breakdown_data_points.data_iter().map(|breakdown|{
+                                breakdown.stacked().iter().map(|x| f64::from(x.1)).collect()
+                            }).collect();
+                            stacked_lines(&names, samples, breakdown_offset, &CPU_BREAKDOWN_COLORS).into_iter().for_each(|line| plot_ui.line(line));
+                        }
+                        plot_ui.line(cpu_line);
+
+                        snapshot.cpus_performance_data_points.iter()
+                            .filter(|x| self.view_state.cpus_on_plot.contains(&x.name))
+                            .for_each(|x|{
+                                let inner_points: Vec<[f64; 2]> = x.plot_points.data_iter().enumerate().map(|(index, &i)| {
+                                    [index as f64, f64::from(i)]
+                                }).collect();
+                                plot_ui.line(Line::new(inner_points).name(&x.name).color(x.color));
+                            });
 
-            ui.separator();
-
-            ui.with_layout(Layout::top_down(Align::Min), |ui|{
-                let network_plot: Plot = Plot::new("NETWORK")
-                    .show_axes([false, true])
-                    .height(0.32 * window_size.y)
-                    .width(0.32 * window_size.x)
-                    .allow_scroll(false)
-                    .allow_drag(false)
-                    .legend(Legend::default().position(Corner::RightTop).background_alpha(0.0))
-                    .reset();
-
-                network_plot.show(ui, |plot_ui: &mut plot::PlotUi|{
-                    mutex_data.network_informations.iter().for_each(|x| {
-                        match &x.network_display {
-                            Some(points) => {
-                                
-                                let transmitted_line: Vec<[f64; 2]> = {
-                                    points.transmitted_plot_points.data_iter().enumerate().map(|(index, &i)| {
-                                        [index as f64, i as f64]
-                                    }).collect()
-                                };
-                                let received_line: Vec<[f64; 2]> = {
-                                    points.received_plot_points.data_iter().enumerate().map(|(index, &i)| {
-                                        [index as f64, i as f64]
-                                    }).collect()
-                                };
-                                plot_ui.line(Line::new(transmitted_line).name(format!("{}. bytes transmitted", x.number)));
-                                plot_ui.line(Line::new(received_line).name(format!("{}. bytes received", x.number)));
+                        plot_ui.set_plot_bounds(plot_bounds);
+                    });
+        
+                    ui.horizontal(|inner_ui|{
+                        inner_ui.vertical(|inner_ui|{
+                            inner_ui.horizontal(|inner_ui|{
+                                inner_ui.label(RichText::new("CPU :"));
+                                inner_ui.colored_label(colors.cpu_usage.0, RichText::new(format!("{}%", snapshot.total_cpu_usage)));
+                            });
+                            inner_ui.menu_button("plot", |inner_ui|{
+                                if snapshot.cpu_breakdown_data_points.data_iter().next().is_some() {
+                                    inner_ui.checkbox(&mut self.view_state.cpu_breakdown_on_plot, "breakdown");
+                                    inner_ui.separator();
+                                }
+                                snapshot.cpus_performance_data_points.iter().for_each(|cpu|{
+                                    let mut is_display_on_plot = self.view_state.cpus_on_plot.contains(&cpu.name);
+                                    if inner_ui.checkbox(&mut is_display_on_plot, &cpu.name).changed() {
+                                        if is_display_on_plot {
+                                            self.view_state.cpus_on_plot.insert(cpu.name.clone());
+                                        } else {
+                                            self.view_state.cpus_on_plot.remove(&cpu.name);
+                                        }
+                                    }
+                                });
+                            });
+                            inner_ui.add_space(3.0);
+                            inner_ui.add_enabled(!self.view_state.cpus_heatmap, egui::DragValue::new(&mut self.view_state.cpus_columns).speed(0.03).clamp_range(1.0..=4.0).suffix(" columns"));
+                            inner_ui.checkbox(&mut self.view_state.cpus_heatmap, "heatmap");
+                            inner_ui.set_min_width(63.0)
+                        });
+                        inner_ui.separator();
+                        inner_ui.vertical(|inner_ui|{
+                            inner_ui.add_space(3.5);
+                            if self.view_state.cpus_heatmap {
+                                cpu_heatmap(inner_ui, &snapshot.cpus_performance_data_points, history_length as usize, colors.cpu_usage.0, &mut self.view_state.cpus_on_plot);
+                                return;
                             }
-                            None => {
-                            }                         
-                        }
+                            snapshot.cpus_performance_data_points.chunks(self.view_state.cpus_columns).for_each(|x|{
+                                inner_ui.horizontal(|inner_ui|{
+                                    for ele in x {
+                                        inner_ui.vertical(|inner_ui|{
+                                            inner_ui.set_min_width(70.0);
+                                            let label = match ele.removed_since {
+                                                Some(_) => inner_ui.label(RichText::new(format!("{}: offline", ele.name)).weak()),
+                                                None => inner_ui.label(format!("{}: {}%",ele.name, ele.usage.round() as i32)),
+                                            };
+                                            if ele.removed_since.is_none() {
+                                                let mut tooltip = format!("{} MHz", ele.frequency);
+                                                if let Some(limits) = &ele.frequency_limits {
+                                                    tooltip += &format!(" ({}-{} MHz, {})", limits.min_mhz, limits.max_mhz, limits.governor);
+                                                }
+                                                if let Some(breakdown) = &ele.breakdown {
+                                                    tooltip += &format!("\n{}", breakdown);
+                                                }
+                                                label.on_hover_text(tooltip);
+                                            }
+                                            inner_ui.add_space(0.7);
+                                        });
+                                    }
+                                });
+                            })
+                        })
                     });
-                    plot_ui.set_plot_bounds(PlotBounds::from_min_max([0.0, 0.0], [20.0, max_y_network_plot_bound]));
-                })
-            });
 
-            ScrollArea::vertical().scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden).show(ui, |inner_ui|{
-                inner_ui.vertical_centered(|inner_ui| {
-                    mutex_data.network_informations.iter_mut().for_each(|net|{
-                        inner_ui.horizontal(|inner_ui| {
-                            inner_ui.checkbox(&mut net.is_display_on_plot, "");
-                            inner_ui.vertical(|inner_ui| {
-                                let mut t1 = RichText::new(format!("{}. {}", net.number, net.interface_name));
-                                let mut t2 = RichText::new(format!("Mac address: {}", net.mac_address));
-                                if !net.is_display_on_plot {
-                                    t1 = t1.weak();
-                                    t2 = t2.weak();
+                    ui.separator();
+
+                    egui::CollapsingHeader::new("frequency and scheduler").id_source("cpu_scheduler").show(ui, |inner_ui|{
+                        let plot_width = 0.3 * window_size.x;
+                        let scheduler = &snapshot.scheduler_informations;
+                        let present_cpus: Vec<_> = snapshot.cpus_performance_data_points.iter().filter(|x| x.removed_since.is_none()).collect();
+
+                        let average_frequency = present_cpus.iter().map(|x| x.frequency).sum::<u64>() / present_cpus.len().max(1) as u64;
+                        inner_ui.label(format!("frequency: {} MHz average", average_frequency));
+                        // a core plugged in later has a shorter history, so each point averages only the cores that have it
+                        let mut frequency_sums: Vec<(f64, usize)> = Vec::new();
+                        present_cpus.iter().for_each(|cpu|{
+                            cpu.frequency_plot_points.data_iter().enumerate().for_each(|(point, &i)|{
+                                match frequency_sums.get_mut(point) {
+                                    Some(value) => *value = (value.0 + f64::from(i), value.1 + 1),
+                                    None => frequency_sums.push((f64::from(i), 1)),
                                 }
-                                inner_ui.label(t1);
-                                inner_ui.label(t2);
-                                inner_ui.separator();
-                            })
+                            });
+                        });
+                        let average_points: Vec<[f64; 2]> = frequency_sums.iter().enumerate().map(|(index, (sum, count))| {
+                            [index as f64, sum / *count as f64]
+                        }).collect();
+                        let mut frequency_lines = vec![Line::new(average_points).name("average MHz")];
+                        present_cpus.iter().filter(|x| self.view_state.cpus_on_plot.contains(&x.name)).for_each(|x|{
+                            frequency_lines.push(Line::new(plot_points(&x.frequency_plot_points)).name(&x.name).color(x.color));
                         });
+                        history_plot(inner_ui, "cpu_frequency", plot_width, frequency_lines);
+
+                        let load_average = |data: &Data<f32>| data.last().copied().unwrap_or(0.0);
+                        inner_ui.label(format!("load average: {:.2} / {:.2} / {:.2}",
+                            load_average(&scheduler.load_average_one), load_average(&scheduler.load_average_five), load_average(&scheduler.load_average_fifteen)));
+                        history_plot(inner_ui, "load_average", plot_width, vec![
+                            Line::new(plot_points(&scheduler.load_average_one)).name("1 min"),
+                            Line::new(plot_points(&scheduler.load_average_five)).name("5 min"),
+                            Line::new(plot_points(&scheduler.load_average_fifteen)).name("15 min"),
+                        ]);
+
+                        if scheduler.procs_running.last().is_some() {
+                            let latest = |data: &Data<f32>| data.last().copied().unwrap_or(0.0).round() as u64;
+                            inner_ui.label(format!("context switches: {}/s, interrupts: {}/s",
+                                latest(&scheduler.context_switches_per_second), latest(&scheduler.interrupts_per_second)));
+                            history_plot(inner_ui, "scheduler_rates", plot_width, vec![
+                                Line::new(plot_points(&scheduler.context_switches_per_second)).name("context switches/s"),
+                                Line::new(plot_points(&scheduler.interrupts_per_second)).name("interrupts/s"),
+                            ]);
+                            inner_ui.label(format!("running: {}, blocked: {}, forks: {}/s",
+                                latest(&scheduler.procs_running), latest(&scheduler.procs_blocked), latest(&scheduler.forks_per_second)));
+                            history_plot(inner_ui, "scheduler_processes", plot_width, vec![
+                                Line::new(plot_points(&scheduler.procs_running)).name("running"),
+                                Line::new(plot_points(&scheduler.procs_blocked)).name("blocked"),
+                                Line::new(plot_points(&scheduler.forks_per_second)).name("forks/s"),
+                            ]);
+                        }
                     });
-                });
-            });
-        });
-
-        SidePanel::left("MEMORY").resizable(false).show(ctx, |ui|{
-            let plot = Plot::new("memory_plot")
-                .show_axes([false, true])
-                .height(0.325 * window_size.y)
-                .width(0.325 * window_size.x)
-                .allow_scroll(false)
-                .allow_drag(false)
-                .legend(Legend::default().position(Corner::LeftTop).background_alpha(0.0))
-                .reset();
-    
-            ui.set_max_width(0.325 * window_size.x);
-            ui.set_max_height(0.325 * window_size.y);
+                    pressure_section(ui, "cpu", &snapshot.cpu_pressure, 0.3 * window_size.x);
 
-            plot.show(ui, |plot_ui|{
-                plot_ui.line(Line::new(memory_points).name("memory %"));
-                plot_ui.line(Line::new(swap_points).name("swap %"));
-                plot_ui.set_plot_bounds(plot_bounds);
-            });
+                    ui.separator();
 
-            ui.add_space(2.0);
+                    ui.label(RichText::new(&self.cpu_informations.cpu_brand).heading().color(colors.accent.0));
+                    match &self.cpu_informations.os_version {
+                        Some(value) => ui.label(RichText::new(format!("os version: {}", value))),
+                        _ => { return; }
+                    };
+                    match &self.cpu_informations.kernel_version {
+                        Some(value) => ui.label(RichText::new(format!("kernel version: {}", value))),
+                        _ => { return; }
+                    };
 
-            let mut memory_section_width: f32 = 0.0;
+                    ui.separator();
+                }
 
-            ui.vertical(|inner_ui|{
-                inner_ui.horizontal(|inner_ui|{
-                    let memory_group = inner_ui.group(|inner_ui|{
-                        let mem_usage = ProcessManagerApp::bytes_to_gb_or_tb_tuple(mutex_data.memory_usage);
-                        let mem_total = ProcessManagerApp::bytes_to_gb_or_tb_tuple(self.memory_informations.total_memory);
-                        inner_ui.label(RichText::new("Memory used:"));
-                        inner_ui.label(RichText::new(format!("{:.2} {}", mem_usage.0, mem_usage.1)).color(Color32::RED));
-                        inner_ui.label("/");
-                        inner_ui.label(format!("{:.2} {}", mem_total.0, mem_total.1));
+                if config.panels.network {
+                    ui.with_layout(Layout::top_down(Align::Min), |ui|{
+                        let network_plot: Plot = Plot::new("NETWORK")
+                            .show_axes([false, true])
+                            .height(0.32 * window_size.y)
+                            .width(0.32 * window_size.x)
+                            .allow_scroll(false)
+                            .allow_drag(false)
+                            .legend(Legend::default().position(Corner::RightTop).background_alpha(0.0))
+                            .y_axis_formatter(|value, _range| format_rate(value))
+                            .label_formatter(|name, value| format!("{}\n{}", name, format_rate(value.y)))
+                            .reset();
+
+                        network_plot.show(ui, |plot_ui: &mut plot::PlotUi|{
+                            snapshot.network_informations.iter()
+                                .filter(|x| !self.view_state.networks_hidden_on_plot.contains(&x.interface_name))
+                                .for_each(|x| {
+                                    let points = &x.network_display;
+                                    let transmitted_line: Vec<[f64; 2]> = {
+                                        points.transmitted_plot_points.data_iter().enumerate().map(|(index, &i)| {
+                                            [index as f64, i]
+                                        }).collect()
+                                    };
+                                    let received_line: Vec<[f64; 2]> = {
+                                        points.received_plot_points.data_iter().enumerate().map(|(index, &i)| {
+                                            [index as f64, i]
+                                        }).collect()
+                                    };
+                                    plot_ui.line(Line::new(transmitted_line).name(format!("{}. transmitted", x.number)));
+                                    plot_ui.line(Line::new(received_line).name(format!("{}. received", x.number)));
+                                });
+                            plot_ui.set_plot_bounds(PlotBounds::from_min_max([0.0, 0.0], [history_length, max_y_network_plot_bound]));
+                        })
                     });
-                    
-                    let swap_group = inner_ui.group(|inner_ui|{
-                        let swap_usage = ProcessManagerApp::bytes_to_gb_or_tb_tuple(mutex_data.swap_usage);
-                        let total_swap = ProcessManagerApp::bytes_to_gb_or_tb_tuple(self.memory_informations.total_swap);
-                        inner_ui.label(RichText::new("Swap used:"));
-                        inner_ui.label(RichText::new(format!("{:.2} {}", swap_usage.0, swap_usage.1)).color(Color32::LIGHT_BLUE));
-                        inner_ui.label("/");
-                        inner_ui.label(format!("{:.2} {}", total_swap.0, total_swap.1));
+
+                    ScrollArea::vertical().scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden).show(ui, |inner_ui|{
+                        inner_ui.vertical_centered(|inner_ui| {
+                            snapshot.network_informations.iter().for_each(|net|{
+                                inner_ui.horizontal(|inner_ui| {
+                                    let mut is_display_on_plot = !self.view_state.networks_hidden_on_plot.contains(&net.interface_name);
+                                    if inner_ui.checkbox(&mut is_display_on_plot, "").changed() {
+                                        if is_display_on_plot {
+                                            self.view_state.networks_hidden_on_plot.remove(&net.interface_name);
+                                        } else {
+                                            self.view_state.networks_hidden_on_plot.insert(net.interface_name.clone());
+                                        }
+                                    }
+                                    inner_ui.vertical(|inner_ui| {
+                                        let mut t1 = RichText::new(format!("{}. {}", net.number, net.interface_name));
+                                        let mut t2 = RichText::new(format!("Mac address: {}", net.mac_address));
+                                        if net.removed_since.is_some() {
+                                            t1 = RichText::new(format!("{}. {} (removed)", net.number, net.interface_name));
+                                        }
+                                        if !is_display_on_plot || net.removed_since.is_some() {
+                                            t1 = t1.weak();
+                                            t2 = t2.weak();
+                                        }
+                                        inner_ui.label(t1);
+                                        inner_ui.label(t2);
+                                        inner_ui.label(RichText::new(interface_summary(&net.details)).weak());
+                                        net.details.addresses.iter().for_each(|address|{
+                                            inner_ui.label(RichText::new(address.to_string()).weak());
+                                        });
+                                        let display = &net.network_display;
+                                        let rate = |data: &Data<f64>| {
+                                            let value = data.last().copied().unwrap_or(0.0);
+                                            match net.details.utilization(value) {
+                                                Some(utilization) => format!("{} ({:.1}%)", format_rate(value), utilization),
+                                                None => format_rate(value),
+                                            }
+                                        };
+                                        inner_ui.label(format!("⏷ {}   ⏶ {}", rate(&display.received_plot_points), rate(&display.transmitted_plot_points)));
+                                        network_counters_section(inner_ui, net, 0.3 * window_size.x);
+                                        inner_ui.separator();
+                                    })
+                                });
+                            });
+                            probes_section(inner_ui, &snapshot, &mut self.view_state.last_export, 0.3 * window_size.x);
+                        });
                     });
-                    memory_section_width = memory_group.response.rect.width() + swap_group.response.rect.width();
-                })
+                }
             });
+        }
+
+        let mut open_views = OpenViews::default();
+        if config.panels.memory || config.panels.disks {
+            SidePanel::left("MEMORY").resizable(false).show(ctx, |ui|{
+                if config.panels.memory {
+                    let plot = Plot::new("memory_plot")
+                        .show_axes([false, true])
+                        .height(0.325 * window_size.y)
+                        .width(0.325 * window_size.x)
+                        .allow_scroll(false)
+                        .allow_drag(false)
+                        .legend(Legend::default().position(Corner::LeftTop).background_alpha(0.0))
+                        .reset();
+    
+                    ui.set_max_width(0.325 * window_size.x);
+                    ui.set_max_height(0.325 * window_size.y);
 
-            ui.add_space(1.0);
+                    plot.show(ui, |plot_ui|{
+                        plot_ui.line(Line::new(memory_points).name("memory %"));
+                        if snapshot.total_swap > 0 {
+                            plot_ui.line(Line::new(swap_points).name("swap %"));
+                        }
+                        plot_ui.set_plot_bounds(plot_bounds);
+                    });
 
-            ui.separator();
+                    let paging = &snapshot.paging_informations;
+                    if paging.is_thrashing(&config.thresholds) {
+                        let latest = |data: &Data<f32>| data.last().copied().unwrap_or(0.0);
+                        ui.label(RichText::new(format!("THRASHING: {:.0} pages/s swapped in, {:.0} pages/s out, {:.0} major faults/s",
+                            latest(&paging.swap_in_per_second), latest(&paging.swap_out_per_second), latest(&paging.major_faults_per_second)))
+                            .color(colors.alert.0).strong());
+                    }
 
-            ui.vertical(|inner_ui|{
-                match &self.system_informations.host_name {
-                    Some(value) => {
-                        inner_ui.horizontal(|inner_ui|{
-                            inner_ui.label(RichText::new("Host name:"));
-                            inner_ui.label(format!("{}", value));
+                    if let Some(latest) = snapshot.memory_breakdown_data_points.last() {
+                        egui::CollapsingHeader::new("memory breakdown").id_source("memory_breakdown").show(ui, |inner_ui|{
+                            let names = MemoryBreakdown::default().stacked().map(|x| x.0);
+                            let percent = |value: u64, total: u64| value as f64 / total.max(1) as f64 * 100.0;
+                            let samples = snapshot.memory_breakdown_data_points.data_iter().map(|breakdown|{
+                                breakdown.stacked().iter().map(|x| percent(x.1, breakdown.total)).collect()
+                            }).collect();
+                            let available_points: Vec<[f64; 2]> = snapshot.memory_breakdown_data_points.data_iter().enumerate().map(|(index, breakdown)|{
+                                [index as f64, 100.0 - percent(breakdown.available, breakdown.total)]
+                            }).collect();
+                            Plot::new("memory_breakdown_plot")
+                                .show_axes([false, true])
+                                .height(0.2 * window_size.y)
+                                .width(0.325 * window_size.x)
+                                .allow_scroll(false)
+                                .allow_drag(false)
+                                .legend(Legend::default().position(Corner::LeftTop).background_alpha(0.0))
+                                .show(inner_ui, |plot_ui|{
+                                    stacked_lines(&names, samples, 0, &MEMORY_BREAKDOWN_COLORS).into_iter().for_each(|line| plot_ui.line(line));
+                                    plot_ui.line(Line::new(available_points).name("not available %").color(colors.memory_usage.0));
+                                    plot_ui.set_plot_bounds(plot_bounds);
+                                });
+
+                            Grid::new("memory_breakdown_grid").num_columns(4).show(inner_ui, |inner_ui|{
+                                let size = |value: u64| {
+                                    let tuple = ProcessManagerApp::bytes_to_gb_or_tb_tuple(value);
+                                    format!("{:.2} {}", tuple.0, tuple.1)
+                                };
+                                let rows = [
+                                    ("available", latest.available),
+                                    ("free", latest.free),
+                                    ("applications", latest.applications()),
+                                    ("buffers", latest.buffers),
+                                    ("page cache", latest.cached),
+                                    ("shared", latest.shared),
+                                    ("slab reclaimable", latest.slab_reclaimable),
+                                    ("slab unreclaimable", latest.slab_unreclaimable),
+                                    ("dirty", latest.dirty),
+                                    ("writeback", latest.writeback),
+                                    ("huge pages", latest.huge_pages_total),
+                                    ("huge pages free", latest.huge_pages_free),
+                                ];
+                                rows.chunks(2).for_each(|pair|{
+                                    pair.iter().for_each(|(name, value)|{
+                                        inner_ui.label(RichText::new(*name).weak());
+                                        inner_ui.label(size(*value));
+                                    });
+                                    inner_ui.end_row();
+                                });
+                            });
                         });
                     }
-                    None => {
+                    if paging.major_faults_per_second.last().is_some() {
+                        egui::CollapsingHeader::new("paging").id_source("memory_paging").show(ui, |inner_ui|{
+                            let latest = |data: &Data<f32>| data.last().copied().unwrap_or(0.0);
+                            inner_ui.label(format!("swap in: {:.0} pages/s, swap out: {:.0} pages/s", latest(&paging.swap_in_per_second), latest(&paging.swap_out_per_second)));
+                            history_plot(inner_ui, "swap_activity", 0.3 * window_size.x, vec![
+                                Line::new(plot_points(&paging.swap_in_per_second)).name("swap in pages/s"),
+                                Line::new(plot_points(&paging.swap_out_per_second)).name("swap out pages/s"),
+                            ]);
+                            inner_ui.label(format!("page faults: {:.0} minor/s, {:.0} major/s", latest(&paging.minor_faults_per_second), latest(&paging.major_faults_per_second)));
+                            history_plot(inner_ui, "page_faults", 0.3 * window_size.x, vec![
+                                Line::new(plot_points(&paging.minor_faults_per_second)).name("minor faults/s"),
+                                Line::new(plot_points(&paging.major_faults_per_second)).name("major faults/s"),
+                            ]);
+                        });
                     }
+                    pressure_section(ui, "memory", &snapshot.memory_pressure, 0.3 * window_size.x);
+
+                    ui.add_space(2.0);
+
+                    let mut memory_section_width: f32 = 0.0;
+
+                    ui.vertical(|inner_ui|{
+                        inner_ui.horizontal(|inner_ui|{
+                            let memory_group = inner_ui.group(|inner_ui|{
+                                let mem_usage = ProcessManagerApp::bytes_to_gb_or_tb_tuple(snapshot.memory_usage);
+                                let mem_total = ProcessManagerApp::bytes_to_gb_or_tb_tuple(self.memory_informations.total_memory);
+                                inner_ui.label(RichText::new("Memory used:"));
+                                inner_ui.label(RichText::new(format!("{:.2} {}", mem_usage.0, mem_usage.1)).color(colors.memory_usage.0));
+                                inner_ui.label("/");
+                                inner_ui.label(format!("{:.2} {}", mem_total.0, mem_total.1));
+                            });
+                    
+                            let swap_group = inner_ui.group(|inner_ui|{
+                                if snapshot.total_swap == 0 {
+                                    inner_ui.label(RichText::new("Swap: not configured").weak());
+                                    return;
+                                }
+                                let swap_usage = ProcessManagerApp::bytes_to_gb_or_tb_tuple(snapshot.swap_usage);
+                                let total_swap = ProcessManagerApp::bytes_to_gb_or_tb_tuple(snapshot.total_swap);
+                                inner_ui.label(RichText::new("Swap used:"));
+                                inner_ui.label(RichText::new(format!("{:.2} {}", swap_usage.0, swap_usage.1)).color(colors.swap_usage.0));
+                                inner_ui.label("/");
+                                inner_ui.label(format!("{:.2} {}", total_swap.0, total_swap.1));
+                            });
+                            memory_section_width = memory_group.response.rect.width() + swap_group.response.rect.width();
+                        })
+                    });
+
+                    ui.add_space(1.0);
+
+                    ui.separator();
                 }
-                inner_ui.horizontal(|inner_ui|{
-                    inner_ui.label(RichText::new("System:"));
-                    inner_ui.label(format!("{}", self.system_informations.system_version_full_name));
+
+                ui.vertical(|inner_ui|{
+                    match &self.system_informations.host_name {
+                        Some(value) => {
+                            inner_ui.horizontal(|inner_ui|{
+                                inner_ui.label(RichText::new("Host name:"));
+                                inner_ui.label(value.to_string());
+                            });
+                        }
+                        None => {
+                        }
+                    }
+                    inner_ui.horizontal(|inner_ui|{
+                        inner_ui.label(RichText::new("System:"));
+                        inner_ui.label(self.system_informations.system_version_full_name.to_string());
+                    });
                 });
-            });
 
-            ui.separator();
+                ui.separator();
             
-            let background_color = Color32::from_rgb(10, 13, 13); 
-            let mut disk_section_width = 0.0;
-
-            ui.horizontal(|inner_ui|{
-                let label = inner_ui.label(RichText::new("Disks:").heading());
-                let group = inner_ui.group(|inner_ui|{
-                    inner_ui.colored_label(Color32::GOLD, "Name");
-                    inner_ui.colored_label(Color32::BROWN, "Mount");
-                    inner_ui.colored_label(Color32::LIGHT_BLUE, "Used space");
-                    inner_ui.colored_label(Color32::LIGHT_GRAY, "Total space");
-                    inner_ui.colored_label(Color32::LIGHT_RED, "Kind");
-                    inner_ui.colored_label(Color32::LIGHT_GREEN, "Fs");
-                    inner_ui.colored_label(Color32::KHAKI, "Removable");
-                });
-                disk_section_width = label.rect.width() + group.response.rect.width() - 5.0;
-            });
+                if config.panels.disks {
+                    let background_color = Color32::from_rgb(10, 13, 13); 
+                    let mut disk_section_width = 0.0;
+
+                    ui.horizontal(|inner_ui|{
+                        let label = inner_ui.label(RichText::new("Disks:").heading());
+                        let group = inner_ui.group(|inner_ui|{
+                            inner_ui.colored_label(Color32::GOLD, "Name");
+                            inner_ui.colored_label(Color32::BROWN, "Mount");
+                            inner_ui.colored_label(Color32::LIGHT_BLUE, "Used space");
+                            inner_ui.colored_label(Color32::LIGHT_GRAY, "Total space");
+                            inner_ui.colored_label(Color32::LIGHT_YELLOW, "Inodes");
+                            inner_ui.colored_label(Color32::LIGHT_RED, "Kind");
+                            inner_ui.colored_label(Color32::LIGHT_GREEN, "Fs");
+                            inner_ui.colored_label(Color32::KHAKI, "Removable");
+                        });
+                        disk_section_width = label.rect.width() + group.response.rect.width() - 5.0;
+                    });
+                    pressure_section(ui, "io", &snapshot.io_pressure, 0.3 * window_size.x);
+                    open_views.block_devices = block_devices_section(ui, &snapshot.block_devices);
 
-            ScrollArea::vertical().scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden).show(ui, |inner_ui|{
-                mutex_data.disks_informations.iter().enumerate().for_each(|(i, disk)|{
-                    inner_ui.group(|inner_ui|{
-                        inner_ui.set_width(disk_section_width);
+                    ScrollArea::vertical().scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden).show(ui, |inner_ui|{
+                        snapshot.disks_informations.iter().enumerate().for_each(|(i, disk)|{
+                            inner_ui.group(|inner_ui|{
+                                inner_ui.set_width(disk_section_width);
                     
-                        inner_ui.horizontal(|inner_ui|{
-                            inner_ui.vertical(|inner_ui|{
-                                inner_ui.label(RichText::new(format!("{}", disk.name)).size(12.0).underline().color(Color32::GOLD).background_color(background_color).monospace());
-                                inner_ui.add_space(1.7);
-                                inner_ui.label(RichText::new(format!("{}", disk.mount_point)).size(12.0).color(Color32::BROWN).background_color(background_color).monospace());
-                                inner_ui.label(RichText::new(format!("{}", disk.used_space)).size(12.0).color(Color32::LIGHT_BLUE).background_color(background_color).monospace());
-                                inner_ui.label(RichText::new(format!("{}", disk.total_space)).size(12.0).color(Color32::LIGHT_GRAY).background_color(background_color).monospace());
-                                inner_ui.label(RichText::new(format!("{}", disk.kind)).size(12.0).color(Color32::LIGHT_RED).background_color(background_color).monospace());
-                                inner_ui.label(RichText::new(format!("{}", disk.file_system)).size(12.0).color(Color32::LIGHT_GREEN).background_color(background_color).monospace());
-                                inner_ui.label(RichText::new(format!("{}", disk.is_removable)).size(12.0).color(Color32::KHAKI).background_color(background_color).monospace());
-                            });
-                            Plot::new(i)
-                                .show_axes([false, true])
-                                .allow_scroll(false)
-                                .allow_drag(false)
-                                .legend(Legend::default().background_alpha(0.0).position(Corner::RightTop))
-                                .show(inner_ui, |plot_ui|{
-                                    let transfer_line: Vec<[f64; 2]> = {
-                                        disk.plot_points.data_iter().enumerate().map(|(index, &i)| {
-                                            [index as f64, i as f64]
-                                        }).collect()
-                                    };
-                                    plot_ui.line(Line::new(transfer_line).name("transfer_rate(KB/s)").color(Color32::GREEN).width(0.4));
-                                    plot_ui.set_plot_bounds(PlotBounds::from_min_max([0.0, 0.0], [20.0, (disk.y_max_bound + (disk.y_max_bound * 0.23)) as f64]));
+                                inner_ui.horizontal(|inner_ui|{
+                                    inner_ui.vertical(|inner_ui|{
+                                        match disk.removed_since {
+                                            Some(_) => inner_ui.label(RichText::new(format!("{} (removed)", disk.name.trim_end())).size(12.0).underline().weak().background_color(background_color).monospace()),
+                                            None => inner_ui.label(RichText::new(disk.name.to_string()).size(12.0).underline().color(Color32::GOLD).background_color(background_color).monospace()),
+                                        };
+                                        inner_ui.add_space(1.7);
+                                        inner_ui.label(RichText::new(disk.mount_point.to_string()).size(12.0).color(Color32::BROWN).background_color(background_color).monospace());
+                                        // the other fields come padded to the longest of them
+                                        let padded = |text: String| format!("{:<width$}", text, width = disk.name.len());
+                                        let space = |bytes: u64| {
+                                            let tuple = ProcessManagerApp::bytes_to_gb_or_tb_tuple(bytes);
+                                            padded(format!("{:.2} {}", tuple.0, tuple.1))
+                                        };
+                                        let inodes = match disk.space.inodes {
+                                            Some(inodes) => format!("{:.1}% of {}", inodes.used_percent(), inodes.total),
+                                            None => String::from("n/a"),
+                                        };
+                                        inner_ui.label(RichText::new(space(disk.space.used())).size(12.0).color(Color32::LIGHT_BLUE).background_color(background_color).monospace());
+                                        inner_ui.label(RichText::new(space(disk.space.total)).size(12.0).color(Color32::LIGHT_GRAY).background_color(background_color).monospace());
+                                        inner_ui.label(RichText::new(padded(inodes)).size(12.0).color(Color32::LIGHT_YELLOW).background_color(background_color).monospace());
+                                        inner_ui.label(RichText::new(disk.kind.to_string()).size(12.0).color(Color32::LIGHT_RED).background_color(background_color).monospace());
+                                        inner_ui.label(RichText::new(disk.file_system.to_string()).size(12.0).color(Color32::LIGHT_GREEN).background_color(background_color).monospace());
+                                        inner_ui.label(RichText::new(disk.is_removable.to_string()).size(12.0).color(Color32::KHAKI).background_color(background_color).monospace());
+                                    });
+                                    Plot::new(i)
+                                        .show_axes([false, true])
+                                        .allow_scroll(false)
+                                        .allow_drag(false)
+                                        .legend(Legend::default().background_alpha(0.0).position(Corner::RightTop))
+                                        .show(inner_ui, |plot_ui|{
+                                            let read_line = disk_io_points(&disk.io_data_points, |x| x.read_kb_per_second);
+                                            let write_line = disk_io_points(&disk.io_data_points, |x| x.write_kb_per_second);
+                                            plot_ui.line(Line::new(read_line).name("read(KB/s)").color(colors.disk_transfer.0).width(0.4));
+                                            plot_ui.line(Line::new(write_line).name("write(KB/s)").color(colors.disk_write.0).width(0.4));
+                                            plot_ui.set_plot_bounds(PlotBounds::from_min_max([0.0, 0.0], [history_length, (disk.y_max_bound + (disk.y_max_bound * 0.23)) as f64]));
+                                        });
                                 });
+                                if disk.removed_since.is_none() {
+                                    disk_growth_label(inner_ui, disk, &config.thresholds, colors.alert.0);
+                                }
+                                disk_io_section(inner_ui, i, disk, disk_section_width);
+                            });
                         });
                     });
-                });
+                }
             });
-        });
+        }
 
-        SidePanel::left("Processes").resizable(false).show(ctx, |ui|{
-            ui.set_width(0.355 * window_size.x);
+        if config.panels.sensors {
+            SidePanel::right("SENSORS").resizable(true).default_width(0.2 * window_size.x).show(ctx, |ui|{
+                ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |inner_ui|{
+                    if snapshot.sensors_informations.is_empty() {
+                        inner_ui.label(RichText::new("no readable sensors").weak());
+                    }
+                    let plot_width = inner_ui.available_width();
+                    SensorKind::ALL.iter().for_each(|kind|{
+                        let sensors: Vec<_> = snapshot.sensors_informations.iter().filter(|x| x.id.kind == *kind).collect();
+                        if sensors.is_empty() {
+                            return;
+                        }
+                        inner_ui.label(RichText::new(kind.to_string()).heading().color(colors.accent.0));
+                        let lines = sensors.iter().map(|x| Line::new(plot_points(&x.plot_points)).name(&x.id.label)).collect();
+                        history_plot(inner_ui, &format!("sensors_{}", kind), plot_width, lines);
+
+                        Grid::new(format!("sensors_grid_{}", kind)).num_columns(4).striped(true).show(inner_ui, |inner_ui|{
+                            inner_ui.label(RichText::new("sensor").weak());
+                            inner_ui.label(RichText::new("current").weak());
+                            inner_ui.label(RichText::new("max").weak());
+                            inner_ui.label(RichText::new("critical").weak());
+                            inner_ui.end_row();
 
-            match mutex_data.clicked_process {
-                Some(value) => {
-                    Grid::new("ClickedProcess")
-                    .num_columns(5)
-                    .striped(false)
-                    .spacing([17.0, 2.0])
-                    .show(ui, |inner_ui| {
-                        let color = Color32::LIGHT_BLUE;
-                        columns_definition_display(mutex_data, inner_ui, color);
-                        inner_ui.add(Label::new(RichText::new(format!("{}", '🗙')).color(color)).sense(Sense::click())).clicked().then(||{
-                            mutex_data.clicked_process = None;
+                            let limit = |value: Option<f32>| match value {
+                                Some(value) => format!("{:.0} {}", value, kind.unit()),
+                                None => String::from("-"),
+                            };
+                            sensors.iter().for_each(|sensor|{
+                                let mut current = RichText::new(format!("{:.1} {}", sensor.current, kind.unit()));
+                                if sensor.is_alerting(&config.thresholds) {
+                                    current = current.color(colors.alert.0).strong();
+                                }
+                                match sensor.removed_since {
+                                    Some(_) => inner_ui.label(RichText::new(format!("{} (removed)", sensor.id.label)).weak()),
+                                    None => inner_ui.label(&sensor.id.label),
+                                };
+                                inner_ui.label(current);
+                                inner_ui.label(limit(sensor.max));
+                                inner_ui.label(limit(sensor.critical));
+                                inner_ui.end_row();
+                            });
                         });
-                        
-                        inner_ui.end_row();
-                        
-                        let clicked_process = &mutex_data.process_informations.get(&value);
+                        inner_ui.separator();
+                    });
+                });
+            });
+        }
 
-                        match clicked_process {
-                            Some(clicked_process) => {
+        if config.panels.processes {
+            SidePanel::left("Processes").resizable(false).show(ctx, |ui|{
+                ui.set_width(0.355 * window_size.x);
+
+                egui::CollapsingHeader::new(RichText::new("who has this file open").color(colors.accent.0)).id_source("open_file_search").show(ui, |inner_ui|{
+                    inner_ui.horizontal(|inner_ui|{
+                        let response = inner_ui.text_edit_singleline(&mut self.view_state.open_file_query)
+                            .on_hover_text("part of a path, socket address or pipe");
+                        let submitted = response.lost_focus() && inner_ui.input(|x| x.key_pressed(egui::Key::Enter));
+                        if (inner_ui.button("find").clicked() || submitted) && !self.view_state.open_file_query.trim().is_empty() {
+                            let _ = self.sampler_commands.send(SamplerCommand::FindOpenFile(self.view_state.open_file_query.trim().to_string()));
+                        }
+                    });
+                    if let Some(search) = &snapshot.open_file_search {
+                        match &search.matches {
+                            Ok(matches) if matches.is_empty() => {
+                                inner_ui.label(RichText::new(format!("nothing has \"{}\" open", search.query)).weak());
+                            }
+                            Ok(matches) => {
+                                ScrollArea::vertical().id_source("open_file_matches").max_height(150.0).show(inner_ui, |inner_ui|{
+                                    Grid::new("open_file_matches_grid").num_columns(4).striped(true).show(inner_ui, |inner_ui|{
+                                        matches.iter().for_each(|x|{
+                                            inner_ui.add(Label::new(format!("{} ({})", x.process_name, x.pid)).sense(Sense::click()))
+                                                .on_hover_text("details")
+                                                .clicked()
+                                                .then(||{
+                                                    self.view_state.inspected_process = Some(x.pid);
+                                                    self.view_state.process_details_tab = ProcessDetailsTab::Files;
+                                                    let _ = self.sampler_commands.send(SamplerCommand::InspectProcess(Some(x.pid)));
+                                                });
+                                            inner_ui.label(x.file.fd.to_string());
+                                            inner_ui.label(x.file.kind.to_string());
+                                            inner_ui.label(&x.file.target);
+                                            inner_ui.end_row();
+                                        });
+                                    });
+                                });
+                            }
+                            Err(e) => {
+                                inner_ui.label(RichText::new(e).weak());
+                            }
+                        }
+                    }
+                });
+
+                let process_io_view = egui::CollapsingHeader::new(RichText::new("disk I/O by process").color(colors.accent.0)).id_source("process_io").show(ui, |inner_ui|{
+                    let process_io = match &snapshot.process_io {
+                        Ok(value) => value,
+                        Err(e) => {
+                            inner_ui.label(RichText::new(e).weak());
+                            return;
+                        }
+                    };
+                    inner_ui.checkbox(&mut self.view_state.process_io_only_active, "only processes doing I/O");
+                    ScrollArea::vertical().id_source("process_io_scroll").max_height(200.0).show(inner_ui, |inner_ui|{
+                        Grid::new("process_io_grid").num_columns(7).striped(true).show(inner_ui, |inner_ui|{
+                            let color = colors.accent.0;
+                            inner_ui.colored_label(color, "NAME");
+                            inner_ui.colored_label(color, "READ ⏷").on_hover_text("sorted by read + write, bytes that reached the storage layer");
+                            inner_ui.colored_label(color, "WRITE");
+                            inner_ui.colored_label(color, "CANCELLED").on_hover_text("writes truncated away before they reached the disk");
+                            inner_ui.colored_label(color, "SYSCR/s");
+                            inner_ui.colored_label(color, "SYSCW/s");
+                            inner_ui.colored_label(color, "PRIO");
+                            inner_ui.end_row();
+                            process_io.iter()
+                                .filter(|x| !self.view_state.process_io_only_active || x.bytes_per_second() > 0.0 || x.cancelled_write_bytes_per_second > 0.0)
+                                .for_each(|x|{
+                                    inner_ui.add(Label::new(format!("{} ({})", x.name, x.pid)).sense(Sense::click()))
+                                        .on_hover_text("details and I/O priority")
+                                        .clicked()
+                                        .then(||{
+                                            self.view_state.inspected_process = Some(x.pid);
+                                            self.view_state.process_details_tab = ProcessDetailsTab::Io;
+                                            let _ = self.sampler_commands.send(SamplerCommand::InspectProcess(Some(x.pid)));
+                                        });
+                                    inner_ui.label(format_rate(f64::from(x.read_bytes_per_second)));
+                                    inner_ui.label(format_rate(f64::from(x.write_bytes_per_second)));
+                                    inner_ui.label(format_rate(f64::from(x.cancelled_write_bytes_per_second)));
+                                    inner_ui.label(format!("{:.0}", x.read_syscalls_per_second));
+                                    inner_ui.label(format!("{:.0}", x.write_syscalls_per_second));
+                                    match x.priority {
+                                        Some(priority) => inner_ui.label(priority.to_string()),
+                                        None => inner_ui.label(RichText::new("?").weak()),
+                                    };
+                                    inner_ui.end_row();
+                                });
+                        });
+                    });
+                });
+                open_views.process_io = process_io_view.openness > 0.0;
+
+                if let Some(value) = self.view_state.clicked_process {
+                    Grid::new("ClickedProcess")
+                    .num_columns(7)
+                    .striped(false)
+                    .spacing([17.0, 2.0])
+                    .show(ui, |inner_ui| {
+                        let color = colors.selected_process.0;
+                        columns_definition_display(&mut self.view_state.processes_sort_option, inner_ui, color);
+                        inner_ui.add(Label::new(RichText::new(format!("{}", '🗙')).color(color)).sense(Sense::click())).clicked().then(||{
+                            self.view_state.clicked_process = None;
+                            self.view_state.highlighted_process = None;
+                        });
+                    
+                        inner_ui.end_row();
+                    
+                        let clicked_process = &snapshot.process_informations.get(&value);
+
+                        match clicked_process {
+                            Some(clicked_process) => {
                                 inner_ui.with_layout(Layout::default(), |inner_ui|{
                                     inner_ui.set_min_width(165.0);
                                     inner_ui.set_max_width(165.0);
-                                    inner_ui.colored_label(color, format!("{} {}", clicked_process.name, '⏷'))
+                                    inner_ui.add(Label::new(RichText::new(format!("{} {}", clicked_process.name, '⏷')).color(color)).sense(Sense::click()))
+                                        .on_hover_text("details")
+                                        .clicked()
+                                        .then(||{
+                                            self.view_state.inspected_process = Some(value);
+                                            let _ = self.sampler_commands.send(SamplerCommand::InspectProcess(Some(value)));
+                                        });
                                 });
-                                    
+                                
                                 inner_ui.colored_label(color,format!("{:.1}", clicked_process.cpu));
                                 inner_ui.colored_label(color,format!("{:.1}", clicked_process.memory));
-                                inner_ui.colored_label(color,format!("{:.1}", clicked_process.disk / 1000.0 / 1000.0));
-                                inner_ui.add(Label::new(RichText::new(format!("{}", '🗙')).color(Color32::from_rgb(210, 151, 49))).sense(Sense::click())).clicked().then(||{
-                                    let process_to_kill = mutex_data.system.process(value);
-                                    match process_to_kill {
-                                        Some(value) => {
-                                            value.kill();
-                                        }
-                                        None => {
-                                        }
-                                    }
+                                inner_ui.colored_label(color,format!("{:.1}", clicked_process.disk / 1024.0));
+                                inner_ui.colored_label(color,network_cell(clicked_process.network, self.view_state.open_views.process_network));
+                                inner_ui.colored_label(color,format!("{:.0}", clicked_process.major_faults_per_second));
+                                inner_ui.add(Label::new(RichText::new(format!("{}", '🗙')).color(colors.accent.0)).sense(Sense::click())).clicked().then(||{
+                                    let _ = self.sampler_commands.send(SamplerCommand::KillProcess(value));
                                 });
-        
+    
                                 inner_ui.end_row();
-        
-                                let sorted_processes = match mutex_data.processes_sort_option {
+    
+                                let sorted_processes = match self.view_state.processes_sort_option {
                                     ProcessesSortOption::Memory => {
                                         clicked_process.child_processes.iter()
-                                            .sorted_by(|a, b| a.memory.partial_cmp(&b.memory).unwrap().reverse())
+                                            .sorted_by(|a, b| a.memory.total_cmp(&b.memory).reverse())
                                     }
                                     ProcessesSortOption::Cpu => {
                                         clicked_process.child_processes.iter()
-                                            .sorted_by(|a, b| a.cpu.partial_cmp(&b.cpu).unwrap().reverse())
+                                            .sorted_by(|a, b| a.cpu.total_cmp(&b.cpu).reverse())
                                     }
                                     ProcessesSortOption::Disk => {
                                         clicked_process.child_processes.iter()
-                                            .sorted_by(|a, b| a.disk.partial_cmp(&b.disk).unwrap().reverse())
+                                            .sorted_by(|a, b| a.disk.total_cmp(&b.disk).reverse())
+                                    }
+                                    ProcessesSortOption::Network => {
+                                        clicked_process.child_processes.iter()
+                                            .sorted_by(|a, b| a.network.total_cmp(&b.network).reverse())
+                                    }
+                                    ProcessesSortOption::MajorFaults => {
+                                        clicked_process.child_processes.iter()
+                                            .sorted_by(|a, b| a.major_faults_per_second.total_cmp(&b.major_faults_per_second).reverse())
                                     }
                                 };
-                                
+                            
                                 sorted_processes.for_each(|process|{
-                                    println!("{}", process.pid);
                                     inner_ui.with_layout(Layout::default(), |inner_ui|{
                                         inner_ui.set_min_width(165.0);
                                         inner_ui.set_max_width(165.0);     
-                                        inner_ui.label(format!("{}", process.name));  
+                                        let name = if self.view_state.highlighted_process == Some(process.pid) {
+                                            RichText::new(format!("▶ {}", process.name)).color(colors.accent.0).strong()
+                                        } else {
+                                            RichText::new(process.name.to_string())
+                                        };
+                                        inner_ui.add(Label::new(name).sense(Sense::click()))
+                                            .on_hover_text("details")
+                                            .clicked()
+                                            .then(||{
+                                                self.view_state.inspected_process = Some(process.pid);
+                                                let _ = self.sampler_commands.send(SamplerCommand::InspectProcess(Some(process.pid)));
+                                            });
                                     });
                                     inner_ui.label(format!("{:.1}", process.cpu));
                                     inner_ui.label(format!("{:.1}", process.memory));
-                                    inner_ui.label(format!("{:.1}", process.disk / 1000.0 / 1000.0));
-                                    inner_ui.add(Label::new(RichText::new(format!("{}", '🗙')).color(Color32::from_rgb(210, 151, 49))).sense(Sense::click())).clicked().then(||{
-                                        let process_to_kill = mutex_data.system.process(process.pid);
-                                        match process_to_kill {
-                                            Some(value) => {
-                                                value.kill();
-                                            }
-                                            None => {
-                                            }
-                                        }
+                                    inner_ui.label(format!("{:.1}", process.disk / 1024.0));
+                                    inner_ui.label(network_cell(process.network, self.view_state.open_views.process_network));
+                                    inner_ui.label(format!("{:.0}", process.major_faults_per_second));
+                                    inner_ui.add(Label::new(RichText::new(format!("{}", '🗙')).color(colors.accent.0)).sense(Sense::click())).clicked().then(||{
+                                        let _ = self.sampler_commands.send(SamplerCommand::KillProcess(process.pid));
                                     });
                                     inner_ui.end_row();
                                 });
@@ -815,98 +1077,812 @@ impl eframe::App for ProcessManagerApp {
 
                     });
                 }
-                None => {
 
-                }
-            }
+                ui.separator();
 
-            ui.separator();
+                ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |inner_ui|{
+                    Grid::new("grid1")
+                        .num_columns(7)
+                        .striped(false)
+                        .spacing([17.0, 2.0])
+                        .show(inner_ui, |inner_ui| {
+                            let color = colors.accent.0;
+                            columns_definition_display(&mut self.view_state.processes_sort_option, inner_ui, color);
 
-            ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |inner_ui|{
-                Grid::new("grid1")
-                    .num_columns(5)
-                    .striped(false)
-                    .spacing([17.0, 2.0])
-                    .show(inner_ui, |inner_ui| {
-                        let color = Color32::from_rgb(210, 151, 49);
-                        columns_definition_display(mutex_data, inner_ui, color);
-
-                        inner_ui.end_row();
+                            inner_ui.end_row();
 
-                        let processes = &mutex_data.process_informations;
-                        let sorted_option = &mutex_data.processes_sort_option;
+                            let processes = &snapshot.process_informations;
+                            let sorted_option = &self.view_state.processes_sort_option;
 
-                        let sorted_processes = get_sorted_processes(sorted_option, processes);
+                            let sorted_processes = get_sorted_processes(sorted_option, processes);
 
-                        sorted_processes.for_each(|process|{
+                            sorted_processes.for_each(|process|{
                             
-                            inner_ui.with_layout(Layout::default(), |inner_ui|{
-                                inner_ui.set_min_width(165.0);
-                                inner_ui.set_max_width(165.0);     
-                                inner_ui.add(Label::new(format!("{}", process.1.name)).sense(Sense::click())).clicked().then(||{
-                                    match &mutex_data.clicked_process {
-                                        Some(value) => {
-                                            if *value != *process.0 {
-                                                mutex_data.clicked_process = Some(*process.0)
+                                inner_ui.with_layout(Layout::default(), |inner_ui|{
+                                    inner_ui.set_min_width(165.0);
+                                    inner_ui.set_max_width(165.0);     
+                                    inner_ui.add(Label::new(process.1.name.to_string()).sense(Sense::click())).clicked().then(||{
+                                        self.view_state.highlighted_process = None;
+                                        match &self.view_state.clicked_process {
+                                            Some(value) => {
+                                                if *value != *process.0 {
+                                                    self.view_state.clicked_process = Some(*process.0)
+                                                }
+                                                else {
+                                                    self.view_state.clicked_process = None
+                                                }
                                             }
-                                            else {
-                                                mutex_data.clicked_process = None
+                                            None => {
+                                                self.view_state.clicked_process = Some(*process.0)
                                             }
                                         }
-                                        None => {
-                                            mutex_data.clicked_process = Some(*process.0)
-                                        }
-                                    }
+                                    });
                                 });
-                            });
                                 
-                            inner_ui.label(format!("{:.1}", process.1.cpu));
-                            inner_ui.label(format!("{:.1}", process.1.memory));
-                            inner_ui.label(format!("{:.1}", process.1.disk / 1000.0 / 1000.0));
-
-                            inner_ui.add(Label::new(RichText::new(format!("{}", '🗙')).color(color)).sense(Sense::click())).clicked().then(||{
-                                let process_to_kill = mutex_data.system.process(*process.0);
-                                match process_to_kill {
-                                    Some(value) => {
-                                        value.kill();
-                                    }
-                                    None => {
-                                    }
-                                }
+                                inner_ui.label(format!("{:.1}", process.1.cpu));
+                                inner_ui.label(format!("{:.1}", process.1.memory));
+                                inner_ui.label(format!("{:.1}", process.1.disk / 1024.0));
+                                inner_ui.label(network_cell(process.1.network, self.view_state.open_views.process_network));
+                                inner_ui.label(format!("{:.0}", process.1.major_faults_per_second));
+
+                                inner_ui.add(Label::new(RichText::new(format!("{}", '🗙')).color(color)).sense(Sense::click())).clicked().then(||{
+                                    let _ = self.sampler_commands.send(SamplerCommand::KillProcess(*process.0));
+                                });
+                                inner_ui.end_row();
                             });
-                            inner_ui.end_row();
                         });
                     });
                 });
+        }
+        let details_tab_open = |tab: ProcessDetailsTab| self.view_state.inspected_process.is_some() && self.view_state.process_details_tab == tab;
+        open_views.process_io |= details_tab_open(ProcessDetailsTab::Io);
+        open_views.process_network = config.panels.processes
+            && (matches!(self.view_state.processes_sort_option, ProcessesSortOption::Network) || details_tab_open(ProcessDetailsTab::Network));
+        if open_views != self.view_state.open_views {
+            self.view_state.open_views = open_views;
+            let _ = self.sampler_commands.send(SamplerCommand::SetOpenViews(open_views));
+        }
+
+        if let Some(pid) = self.view_state.inspected_process {
+            let mut open = true;
+            egui::Window::new("process details").id(egui::Id::new("process_details")).open(&mut open).default_size([560.0, 420.0]).show(ctx, |ui|{
+                let details = match snapshot.process_details.as_ref().filter(|x| x.pid == pid) {
+                    Some(value) => value,
+                    None => {
+                        ui.label(RichText::new(format!("process {} is gone or not sampled yet", pid)).weak());
+                        return;
+                    }
+                };
+                ui.label(RichText::new(format!("{} ({})", details.name, details.pid)).heading().color(colors.accent.0));
+                ui.horizontal(|inner_ui|{
+                    inner_ui.selectable_value(&mut self.view_state.process_details_tab, ProcessDetailsTab::Memory, "memory");
+                    inner_ui.selectable_value(&mut self.view_state.process_details_tab, ProcessDetailsTab::Files, "files");
+                    inner_ui.selectable_value(&mut self.view_state.process_details_tab, ProcessDetailsTab::Threads, "threads");
+                    inner_ui.selectable_value(&mut self.view_state.process_details_tab, ProcessDetailsTab::Network, "network");
+                    inner_ui.selectable_value(&mut self.view_state.process_details_tab, ProcessDetailsTab::Io, "I/O");
+                });
+                ui.separator();
+                match self.view_state.process_details_tab {
+                    ProcessDetailsTab::Memory => process_memory_tab(ui, &details.memory, &mut self.view_state.mappings_sorted_by_growth, colors.alert.0),
+                    ProcessDetailsTab::Files => process_files_tab(ui, &details.open_files, colors.alert.0),
+                    ProcessDetailsTab::Threads => process_threads_tab(ui, &details.threads, colors.alert.0),
+                    ProcessDetailsTab::Network => process_network_tab(ui, details, &snapshot.connections),
+                    ProcessDetailsTab::Io => {
+                        if let Some(priority) = process_io_tab(ui, pid, &snapshot.process_io, &mut self.view_state.io_priority_edit) {
+                            let _ = self.sampler_commands.send(SamplerCommand::SetIoPriority(pid, priority));
+                        }
+                    }
+                }
             });
+            if !open {
+                self.view_state.inspected_process = None;
+                let _ = self.sampler_commands.send(SamplerCommand::InspectProcess(None));
+            }
+        }
+
         ctx.request_repaint_after(Duration::from_millis(33));
     }
 }
 
+/// Key of the group `pid` is listed under, processes are grouped by their parent.
+fn process_group(processes: &HashMap<Pid, ProcessInformations>, pid: Pid) -> Option<Pid> {
+    processes.iter()
+        .find(|(_, group)| group.child_processes.iter().any(|x| x.pid == pid))
+        .map(|(key, _)| *key)
+}
+
+/// The NET column is only sampled while something needs it, a dash stands in for the missing value.
+fn network_cell(bytes_per_second: f32, sampled: bool) -> String {
+    if sampled {
+        format!("{:.1}", bytes_per_second / 1024.0)
+    } else {
+        String::from("-")
+    }
+}
+
 fn get_sorted_processes<'a>(sorted_option: &'a ProcessesSortOption, processes: &'a HashMap<Pid, ProcessInformations>) -> std::vec::IntoIter<(&'a Pid, &'a ProcessInformations)> {
     let sorted_processes = match sorted_option {
         ProcessesSortOption::Memory => {
             processes.iter()
-                .sorted_by(|a, b| a.1.memory.partial_cmp(&b.1.memory).unwrap().reverse())
+                .sorted_by(|a, b| a.1.memory.total_cmp(&b.1.memory).reverse())
         }
         ProcessesSortOption::Cpu => {
             processes.iter()
-                .sorted_by(|a, b| a.1.cpu.partial_cmp(&b.1.cpu).unwrap().reverse())
+                .sorted_by(|a, b| a.1.cpu.total_cmp(&b.1.cpu).reverse())
         }
         ProcessesSortOption::Disk => {
             processes.iter()
-                .sorted_by(|a, b| a.1.disk.partial_cmp(&b.1.disk).unwrap().reverse())
+                .sorted_by(|a, b| a.1.disk.total_cmp(&b.1.disk).reverse())
+        }
+        ProcessesSortOption::Network => {
+            processes.iter()
+                .sorted_by(|a, b| a.1.network.total_cmp(&b.1.network).reverse())
+        }
+        ProcessesSortOption::MajorFaults => {
+            processes.iter()
+                .sorted_by(|a, b| a.1.major_faults_per_second.total_cmp(&b.1.major_faults_per_second).reverse())
         }
     };
     sorted_processes
 }
 
-fn columns_definition_display(mutex_data: &mut ProcessManagerAppMutexData, inner_ui: &mut Ui, color: Color32) {
+/// Round trip and loss of every configured probe, plus the snapshot export.
+fn probes_section(ui: &mut Ui, snapshot: &Snapshot, last_export: &mut Option<Result<PathBuf, String>>, width: f32) {
+    egui::CollapsingHeader::new("probes").id_source("probes").default_open(!snapshot.probes.is_empty()).show(ui, |inner_ui|{
+        if snapshot.probes.is_empty() {
+            inner_ui.label(RichText::new("no [[probes]] in the config").weak());
+        }
+        snapshot.probes.iter().for_each(|probe|{
+            let last = match probe.round_trips.last() {
+                Some(Some(round_trip)) => format!("{:.1} ms", round_trip),
+                Some(None) => String::from("lost"),
+                None => String::from("waiting"),
+            };
+            let label = inner_ui.label(format!("{} ({} {}): {}, {:.0}% loss, {} sent", probe.name, probe.kind, probe.target, last, probe.loss_percent(), probe.sent));
+            if let Some(e) = &probe.last_error {
+                label.on_hover_text(e);
+            }
+        });
+        if !snapshot.probes.is_empty() {
+            let round_trip_lines = snapshot.probes.iter().map(|probe|{
+                // a lost attempt leaves a gap in the line
+                let points: Vec<[f64; 2]> = probe.round_trips.data_iter().enumerate()
+                    .filter_map(|(index, x)| x.map(|round_trip| [index as f64, f64::from(round_trip)]))
+                    .collect();
+                Line::new(points).name(format!("{} ms", probe.name))
+            }).collect();
+            history_plot(inner_ui, "probes_round_trip_plot", width, round_trip_lines);
+            let loss_lines = snapshot.probes.iter().map(|probe|{
+                let attempts: Vec<&Option<f32>> = probe.round_trips.data_iter().collect();
+                let points: Vec<[f64; 2]> = (0..attempts.len()).map(|index|{
+                    let window = &attempts[index.saturating_sub(PROBE_LOSS_WINDOW - 1)..=index];
+                    let lost = window.iter().filter(|x| x.is_none()).count();
+                    [index as f64, lost as f64 / window.len() as f64 * 100.0]
+                }).collect();
+                Line::new(points).name(format!("{} loss %", probe.name))
+            }).collect();
+            history_plot(inner_ui, "probes_loss_plot", width, loss_lines);
+        }
+        inner_ui.horizontal(|inner_ui|{
+            inner_ui.button("export snapshot").on_hover_text("usage, interface rates and probe results as JSON").clicked().then(||{
+                *last_export = Some(export::export_snapshot(snapshot));
+            });
+            match last_export {
+                Some(Ok(path)) => inner_ui.label(RichText::new(path.display().to_string()).weak()),
+                Some(Err(e)) => inner_ui.colored_label(snapshot.config.colors.alert.0, e.as_str()),
+                None => inner_ui.label(""),
+            };
+        });
+    });
+}
+
+/// `physical, up, carrier, 1000 Mb/s full duplex, MTU 1500`, leaving out what isn't known.
+fn interface_summary(details: &InterfaceDetails) -> String {
+    let mut parts = vec![details.kind.to_string()];
+    if let Some(operstate) = &details.operstate {
+        parts.push(operstate.clone());
+    }
+    if let Some(carrier) = details.carrier {
+        parts.push(String::from(if carrier { "carrier" } else { "no carrier" }));
+    }
+    match (details.speed_mbps, &details.duplex) {
+        (Some(speed), Some(duplex)) => parts.push(format!("{} Mb/s {} duplex", speed, duplex)),
+        (Some(speed), None) => parts.push(format!("{} Mb/s", speed)),
+        _ => {}
+    }
+    if let Some(mtu) = details.mtu {
+        parts.push(format!("MTU {}", mtu));
+    }
+    parts.join(", ")
+}
+
+/// Packets, errors and drops per second of one interface and what it moved since the monitor started.
+fn network_counters_section(ui: &mut Ui, net: &NetworkInformations, width: f32) {
+    egui::CollapsingHeader::new("packets, errors and drops").id_source(format!("{}_network_counters", net.interface_name)).show(ui, |inner_ui|{
+        let totals = &net.totals;
+        let bytes = |value: u64| {
+            let (value, unit) = scaled_bytes(value as f64);
+            format!("{:.1} {}", value, unit)
+        };
+        Grid::new(format!("{}_network_totals", net.interface_name)).num_columns(2).show(inner_ui, |inner_ui|{
+            inner_ui.label(RichText::new("since start").weak());
+            inner_ui.label(format!("⏷ {} ({} packets)   ⏶ {} ({} packets)", bytes(totals.received), totals.packets_received, bytes(totals.transmitted), totals.packets_transmitted));
+            inner_ui.end_row();
+            inner_ui.label(RichText::new("errors").weak());
+            inner_ui.label(format!("{} since start, {} in / {} out since boot", totals.errors, net.total_errors_on_received, net.total_errors_on_transmitted));
+            inner_ui.end_row();
+            inner_ui.label(RichText::new("drops").weak());
+            match net.last_drops {
+                Some(_) => inner_ui.label(format!("{} since start", totals.drops)),
+                None => inner_ui.label(RichText::new("not reported on this platform").weak()),
+            };
+            inner_ui.end_row();
+        });
+        let display = &net.network_display;
+        history_plot(inner_ui, &format!("{}_packets_plot", net.interface_name), width, vec![
+            Line::new(plot_points(&display.packets_received_plot_points)).name("packets received/s"),
+            Line::new(plot_points(&display.packets_transmitted_plot_points)).name("packets transmitted/s"),
+        ]);
+        history_plot(inner_ui, &format!("{}_errors_plot", net.interface_name), width, vec![
+            Line::new(plot_points(&display.errors_plot_points)).name("errors/s"),
+            Line::new(plot_points(&display.drops_plot_points)).name("drops/s"),
+        ]);
+    });
+}
+
+/// Growth of the used space over the history window and when the filesystem fills up at that pace.
+fn disk_growth_label(ui: &mut Ui, disk: &DiskInformations, thresholds: &ThresholdsConfig, alert_color: Color32) {
+    let growth_rate = match disk.growth_rate() {
+        Some(value) => value,
+        None => {
+            ui.label(RichText::new("growth: waiting for a second sample").weak());
+            return;
+        }
+    };
+    let (value, unit) = scaled_bytes(growth_rate * 3600.0);
+    match disk.time_to_full() {
+        Some(time_to_full) => {
+            let hours = time_to_full.as_secs_f64() / 3600.0;
+            let text = format!("growing {:.1} {}/h, projected full in {:.1} hours", value, unit, hours);
+            if hours <= f64::from(thresholds.disk_full_hours) {
+                ui.label(RichText::new(format!("⚠ {}", text)).color(alert_color).strong());
+            } else {
+                ui.label(text);
+            }
+        }
+        None => {
+            ui.label(RichText::new(format!("not growing ({:.1} {}/h)", value, unit)).weak());
+        }
+    }
+}
+
+/// Returns whether the tree is folded out, it is only sampled then.
+fn block_devices_section(ui: &mut Ui, block_devices: &Result<Vec<BlockDevice>, String>) -> bool {
+    egui::CollapsingHeader::new("block devices").id_source("block_devices").show(ui, |inner_ui|{
+        match block_devices {
+            Ok(devices) => devices.iter().for_each(|device| block_device_node(inner_ui, device, "block_device")),
+            Err(e) => {
+                inner_ui.label(RichText::new(e).weak());
+            }
+        }
+    }).openness > 0.0
+}
+
+/// One line per device with its I/O below it, what is stacked on it folds out underneath.
+fn block_device_node(ui: &mut Ui, device: &BlockDevice, parent_id: &str) {
+    let id = format!("{}/{}", parent_id, device.name);
+    let (size, unit) = scaled_bytes(device.size as f64);
+    let mut summary = format!("{}  {}  {:.1} {}", device.name, device.kind, size, unit);
+    if let Some(label) = &device.label {
+        summary.push_str(&format!("  ({})", label));
+    }
+    if let Some(rotational) = device.rotational {
+        summary.push_str(if rotational { "  rotational" } else { "  non-rotational" });
+    }
+    if let Some(scheduler) = &device.scheduler {
+        summary.push_str(&format!("  [{}]", scheduler));
+    }
+    if let Some(model) = &device.model {
+        summary.push_str(&format!("  {}", model));
+    }
+    if let Some(serial) = &device.serial {
+        summary.push_str(&format!("  #{}", serial));
+    }
+    let details = |ui: &mut Ui|{
+        device.mount_points.iter().for_each(|x|{
+            ui.label(RichText::new(format!("mounted on {}", x.display())).color(Color32::BROWN));
+        });
+        match device.io {
+            Some(stats) => ui.label(RichText::new(format!(
+                "⏷ {}  ⏶ {}  r/s {:.1}  w/s {:.1}  await {:.2} ms  %util {:.1}",
+                format_rate(f64::from(stats.read_kb_per_second) * 1024.0), format_rate(f64::from(stats.write_kb_per_second) * 1024.0),
+                stats.read_iops, stats.write_iops, stats.await_ms, stats.utilization,
+            )).monospace().weak()),
+            None => ui.label(RichText::new("waiting for a second sample").weak()),
+        };
+    };
+    if device.children.is_empty() {
+        ui.label(RichText::new(summary).monospace());
+        ui.indent(id, details);
+    } else {
+        egui::CollapsingHeader::new(RichText::new(summary).monospace()).id_source(&id).default_open(true).show(ui, |inner_ui|{
+            details(inner_ui);
+            device.children.iter().for_each(|child| block_device_node(inner_ui, child, &id));
+        });
+    }
+}
+
+/// Rates of `pid` from the I/O view and its I/O priority. Returns the priority to set once "apply" is clicked.
+fn process_io_tab(ui: &mut Ui, pid: Pid, process_io: &Result<Vec<ProcessIo>, String>, edit: &mut Option<(Pid, IoPriority)>) -> Option<IoPriority> {
+    let io = match process_io {
+        Ok(value) => value.iter().find(|x| x.pid == pid),
+        Err(e) => {
+            ui.label(RichText::new(e).weak());
+            return None;
+        }
+    };
+    let io = match io {
+        Some(value) => value,
+        None => {
+            ui.label(RichText::new("/proc/<pid>/io is only readable for your own processes without root").weak());
+            return None;
+        }
+    };
+    Grid::new("process_io_counters").num_columns(2).show(ui, |inner_ui|{
+        let rows = [
+            ("read", format_rate(f64::from(io.read_bytes_per_second))),
+            ("written", format_rate(f64::from(io.write_bytes_per_second))),
+            ("cancelled writes", format_rate(f64::from(io.cancelled_write_bytes_per_second))),
+            ("read syscalls", format!("{:.0}/s", io.read_syscalls_per_second)),
+            ("write syscalls", format!("{:.0}/s", io.write_syscalls_per_second)),
+        ];
+        rows.iter().for_each(|(name, value)|{
+            inner_ui.label(RichText::new(*name).weak());
+            inner_ui.label(value);
+            inner_ui.end_row();
+        });
+    });
+    ui.separator();
+
+    let current = match io.priority {
+        Some(value) => value,
+        None => {
+            ui.label(RichText::new("the I/O priority can't be read").weak());
+            return None;
+        }
+    };
+    ui.label(format!("I/O priority: {}", current));
+    if edit.map_or(true, |(edited_pid, _)| edited_pid != pid) {
+        *edit = Some((pid, current));
+    }
+    let mut apply = None;
+    if let Some((_, priority)) = edit {
+        ui.horizontal(|inner_ui|{
+            egui::ComboBox::from_id_source("io_priority_class").selected_text(priority.class.to_string()).show_ui(inner_ui, |inner_ui|{
+                IoPriorityClass::ALL.iter().for_each(|class|{
+                    inner_ui.selectable_value(&mut priority.class, *class, class.to_string());
+                });
+            });
+            inner_ui.add_enabled(priority.class.has_levels(), egui::Slider::new(&mut priority.level, 0..=7).text("level"))
+                .on_hover_text("0 is the highest");
+            if inner_ui.button("apply").clicked() {
+                apply = Some(*priority);
+            }
+        });
+        if priority.class == IoPriorityClass::RealTime {
+            ui.label(RichText::new("the real time class needs CAP_SYS_ADMIN").weak());
+        }
+    }
+    apply
+}
+
+/// iostat's columns for the last interval and their history.
+fn disk_io_section(ui: &mut Ui, index: usize, disk: &DiskInformations, width: f32) {
+    egui::CollapsingHeader::new("iops, latency and utilization").id_source(format!("disk_io_{}", index)).show(ui, |inner_ui|{
+        match disk.io_data_points.last() {
+            Some(stats) => inner_ui.label(RichText::new(format!(
+                "r/s {:.1}   w/s {:.1}   await {:.2} ms   aqu-sz {:.2}   %util {:.1}",
+                stats.read_iops, stats.write_iops, stats.await_ms, stats.queue_depth, stats.utilization,
+            )).monospace()),
+            None => inner_ui.label(RichText::new("waiting for a second sample").weak()),
+        };
+        history_plot(inner_ui, &format!("disk_iops_{}", index), width, vec![
+            Line::new(disk_io_points(&disk.io_data_points, |x| x.read_iops)).name("reads/s"),
+            Line::new(disk_io_points(&disk.io_data_points, |x| x.write_iops)).name("writes/s"),
+        ]);
+        history_plot(inner_ui, &format!("disk_latency_{}", index), width, vec![
+            Line::new(disk_io_points(&disk.io_data_points, |x| x.await_ms)).name("await ms"),
+            Line::new(disk_io_points(&disk.io_data_points, |x| x.queue_depth)).name("queue depth"),
+            Line::new(disk_io_points(&disk.io_data_points, |x| x.utilization)).name("utilization %"),
+        ]);
+    });
+}
+
+/// An empty filter keeps everything, a number matches either port, anything else the state.
+fn connection_matches(connection: &ConnectionInformations, filter: &str) -> bool {
+    if filter.is_empty() {
+        return true;
+    }
+    match filter.parse::<u16>() {
+        Ok(port) => connection.local.port() == port || connection.remote.port() == port,
+        Err(_) => connection.state.to_lowercase().contains(&filter.to_lowercase()),
+    }
+}
+
+/// Binary units, `scaled_bytes(1536.0)` is `(1.5, "KiB")`.
+fn scaled_bytes(bytes: f64) -> (f64, &'static str) {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    (value, UNITS[unit])
+}
+
+fn format_rate(bytes_per_second: f64) -> String {
+    let (value, unit) = scaled_bytes(bytes_per_second);
+    format!("{:.1} {}/s", value, unit)
+}
+
+fn disk_io_points(data: &Data<DiskIoStats>, value: impl Fn(&DiskIoStats) -> f32) -> Vec<[f64; 2]> {
+    data.data_iter().enumerate().map(|(index, stats)| {
+        [index as f64, f64::from(value(stats))]
+    }).collect()
+}
+
+fn plot_points(data: &Data<f32>) -> Vec<[f64; 2]> {
+    data.data_iter().enumerate().map(|(index, &i)| {
+        [index as f64, f64::from(i)]
+    }).collect()
+}
+
+/// Stacked areas from `samples` (one value per layer at every point, bottom layer first).
+/// Every layer is drawn as the sum of itself and the ones below, top first, so each filled area only shows its own band.
+/// The first sample is drawn at `x_offset`.
+fn stacked_lines(names: &[&str], samples: Vec<Vec<f64>>, x_offset: usize, colors: &[Color32]) -> Vec<Line> {
+    let sums: Vec<Vec<f64>> = samples.iter().map(|layers|{
+        layers.iter().scan(0.0, |sum, value| {
+            *sum += value;
+            Some(*sum)
+        }).collect()
+    }).collect();
+    names.iter().enumerate().rev().map(|(layer, name)|{
+        let points: Vec<[f64; 2]> = sums.iter().enumerate().map(|(index, layers)| [(x_offset + index) as f64, layers.get(layer).copied().unwrap_or(0.0)]).collect();
+        Line::new(points).name(*name).color(colors[layer % colors.len()]).fill(0.0)
+    }).collect()
+}
+
+/// Collapsible pressure stall readout of one resource with its own plot. Without PSI there is only a note,
+/// and nothing at all off linux.
+fn pressure_section(ui: &mut Ui, name: &str, pressure: &PressureInformations, width: f32) {
+    if pressure.latest.is_none() && !cfg!(target_os = "linux") {
+        return;
+    }
+    egui::CollapsingHeader::new(format!("{} pressure", name)).id_source(format!("{}_pressure", name)).show(ui, |inner_ui|{
+        let latest = match &pressure.latest {
+            Some(value) => value,
+            None => {
+                inner_ui.label(RichText::new("pressure stall information is not available on this kernel").weak());
+                return;
+            }
+        };
+        let averages = |line: &PressureLine| format!("{:.2}% / {:.2}% / {:.2}%", line.avg10, line.avg60, line.avg300);
+        inner_ui.label(format!("some (10s / 60s / 300s): {}", averages(&latest.some)));
+        if let Some(full) = &latest.full {
+            inner_ui.label(format!("full (10s / 60s / 300s): {}", averages(full)));
+        }
+        history_plot(inner_ui, &format!("{}_pressure_plot", name), width, vec![
+            Line::new(plot_points(&pressure.some_plot_points)).name("some stalled %"),
+            Line::new(plot_points(&pressure.full_plot_points)).name("full stalled %"),
+        ]);
+    });
+}
+
+/// Small auto scaled plot for the secondary histories, x is the sample index like on the big plots.
+fn history_plot(ui: &mut Ui, id: &str, width: f32, lines: Vec<Line>) {
+    Plot::new(id)
+        .show_axes([false, true])
+        .height(70.0)
+        .width(width)
+        .allow_scroll(false)
+        .allow_drag(false)
+        .include_y(0.0)
+        .legend(Legend::default().position(Corner::LeftTop).background_alpha(0.0))
+        .show(ui, |plot_ui|{
+            lines.into_iter().for_each(|line| plot_ui.line(line));
+        });
+}
+
+/// Rows are cores, columns are samples (newest on the right), brighter means busier.
+/// Hovering a cell shows its value, clicking a row toggles the core on the cpu plot.
+fn cpu_heatmap(ui: &mut Ui, cpus: &[CpuData], history_length: usize, color: Color32, cpus_on_plot: &mut HashSet<String>) {
+    if cpus.is_empty() {
+        return;
+    }
+    let columns = history_length + 1;
+    let label_width = 45.0;
+    let row_height = (160.0 / cpus.len() as f32).clamp(2.0, 12.0);
+    let width = ui.available_width().max(label_width + columns as f32);
+    let cell_width = (width - label_width) / columns as f32;
+    let (rect, response) = ui.allocate_exact_size(vec2(width, row_height * cpus.len() as f32), Sense::click());
+    let painter = ui.painter_at(rect);
+    let background = Color32::from_gray(25);
+
+    cpus.iter().enumerate().for_each(|(row, cpu)|{
+        let top = rect.top() + row as f32 * row_height;
+        if row_height >= 8.0 {
+            let label_color = if cpus_on_plot.contains(&cpu.name) { cpu.color } else { ui.visuals().text_color() };
+            painter.text(pos2(rect.left(), top + row_height / 2.0), egui::Align2::LEFT_CENTER, &cpu.name, egui::FontId::monospace(row_height - 1.0), label_color);
+        }
+        // histories are right aligned so a core plugged in later starts mid way
+        let offset = columns.saturating_sub(cpu.plot_points.data_iter().count());
+        cpu.plot_points.data_iter().enumerate().for_each(|(index, &usage)|{
+            let left = rect.left() + label_width + (offset + index) as f32 * cell_width;
+            let cell = Rect::from_min_size(pos2(left, top), vec2(cell_width, row_height));
+            let mut cell_color = lerp_color(background, color, usage / 100.0);
+            if cpu.removed_since.is_some() {
+                cell_color = cell_color.linear_multiply(0.4);
+            }
+            painter.rect_filled(cell.shrink(0.3), 0.0, cell_color);
+        });
+    });
+
+    let hovered = response.hover_pos().and_then(|pos| heatmap_cell(rect, pos, label_width, cell_width, row_height, cpus, columns));
+    if response.clicked() {
+        if let Some((cpu, _)) = hovered {
+            if !cpus_on_plot.remove(&cpu.name) {
+                cpus_on_plot.insert(cpu.name.clone());
+            }
+        }
+    }
+    if let Some((cpu, index)) = hovered {
+        let usage = cpu.plot_points.data_iter().nth(index);
+        let sample_time = cpu.sample_times.data_iter().nth(index);
+        if let (Some(usage), Some(sample_time)) = (usage, sample_time) {
+            let seconds_ago = SystemTime::now().duration_since(*sample_time).unwrap_or_default().as_secs();
+            response.on_hover_text(format!("{}: {:.1}%\n{}s ago", cpu.name, usage, seconds_ago));
+        }
+    }
+}
+
+/// Core and history index under `pos`, `None` over the labels or an empty cell.
+fn heatmap_cell(rect: Rect, pos: egui::Pos2, label_width: f32, cell_width: f32, row_height: f32, cpus: &[CpuData], columns: usize) -> Option<(&CpuData, usize)> {
+    let relative: Vec2 = pos - rect.min;
+    if relative.x < label_width {
+        return None;
+    }
+    let cpu = cpus.get((relative.y / row_height) as usize)?;
+    let column = ((relative.x - label_width) / cell_width) as usize;
+    let offset = columns.saturating_sub(cpu.plot_points.data_iter().count());
+    column.checked_sub(offset).map(|index| (cpu, index))
+}
+
+fn lerp_color(from: Color32, to: Color32, t: f32) -> Color32 {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color32::from_rgb(channel(from.r(), to.r()), channel(from.g(), to.g()), channel(from.b(), to.b()))
+}
+
+fn process_memory_tab(ui: &mut Ui, memory: &Result<ProcessMemory, String>, sorted_by_growth: &mut bool, growth_color: Color32) {
+    let memory = match memory {
+        Ok(value) => value,
+        Err(e) => {
+            ui.label(RichText::new(e).weak());
+            return;
+        }
+    };
+    let size = |value: u64| {
+        let tuple = ProcessManagerApp::bytes_to_gb_or_tb_tuple(value);
+        format!("{:.2} {}", tuple.0, tuple.1)
+    };
+
+    let total = &memory.total;
+    Grid::new("process_memory_total").num_columns(4).show(ui, |inner_ui|{
+        let rows = [
+            ("RSS", total.rss),
+            ("PSS", total.pss),
+            ("USS", total.uss()),
+            ("swap", total.swap),
+            ("shared clean", total.shared_clean),
+            ("shared dirty", total.shared_dirty),
+            ("private clean", total.private_clean),
+            ("private dirty", total.private_dirty),
+        ];
+        rows.chunks(2).for_each(|pair|{
+            pair.iter().for_each(|(name, value)|{
+                inner_ui.label(RichText::new(*name).weak());
+                inner_ui.label(size(*value));
+            });
+            inner_ui.end_row();
+        });
+    });
+    ui.separator();
+    ui.checkbox(sorted_by_growth, "sort by growth since opened");
+
+    let mut groups: Vec<_> = memory.mapping_groups.iter().collect();
+    if *sorted_by_growth {
+        groups.sort_by(|a, b| b.rss_growth.cmp(&a.rss_growth));
+    }
+    ScrollArea::vertical().id_source("process_mappings").show(ui, |inner_ui|{
+        Grid::new("process_mappings_grid").num_columns(8).striped(true).show(inner_ui, |inner_ui|{
+            ["mapping", "count", "virtual", "RSS", "PSS", "private", "swap", "growth"].iter().for_each(|x|{
+                inner_ui.label(RichText::new(*x).weak());
+            });
+            inner_ui.end_row();
+            groups.iter().for_each(|group|{
+                let name = group.name.rsplit('/').next().unwrap_or(&group.name);
+                inner_ui.label(name).on_hover_text(&group.name);
+                inner_ui.label(group.mappings.to_string());
+                inner_ui.label(size(group.size));
+                inner_ui.label(size(group.counters.rss));
+                inner_ui.label(size(group.counters.pss));
+                inner_ui.label(size(group.counters.uss()));
+                inner_ui.label(size(group.counters.swap));
+                let growth = if group.rss_growth < 0 { format!("-{}", size(group.rss_growth.unsigned_abs())) } else { format!("+{}", size(group.rss_growth as u64)) };
+                if group.rss_growth > 0 {
+                    inner_ui.colored_label(growth_color, growth);
+                } else {
+                    inner_ui.label(growth);
+                }
+                inner_ui.end_row();
+            });
+        });
+    });
+}
+
+fn process_files_tab(ui: &mut Ui, open_files: &Result<OpenFiles, String>, alert_color: Color32) {
+    let open_files = match open_files {
+        Ok(value) => value,
+        Err(e) => {
+            ui.label(RichText::new(e).weak());
+            return;
+        }
+    };
+    let count = open_files.entries.len();
+    match open_files.limit {
+        Some(limit) => {
+            let text = format!("{} of {} descriptors open", count, limit);
+            // close to RLIMIT_NOFILE the process starts failing with EMFILE
+            if count as f64 >= limit as f64 * 0.9 {
+                ui.colored_label(alert_color, text);
+            } else {
+                ui.label(text);
+            }
+        }
+        None => {
+            ui.label(format!("{} descriptors open, no limit", count));
+        }
+    }
+    ui.horizontal(|inner_ui|{
+        [OpenFileKind::File, OpenFileKind::Socket, OpenFileKind::Pipe, OpenFileKind::AnonInode, OpenFileKind::Other].iter().for_each(|kind|{
+            let count = open_files.entries.iter().filter(|x| x.kind == *kind).count();
+            if count > 0 {
+                inner_ui.label(RichText::new(format!("{} {}", count, kind)).weak());
+            }
+        });
+    });
+    ui.separator();
+    ScrollArea::vertical().id_source("process_files").show(ui, |inner_ui|{
+        Grid::new("process_files_grid").num_columns(4).striped(true).show(inner_ui, |inner_ui|{
+            ["fd", "kind", "target", "description"].iter().for_each(|x|{
+                inner_ui.label(RichText::new(*x).weak());
+            });
+            inner_ui.end_row();
+            open_files.entries.iter().for_each(|file|{
+                inner_ui.label(file.fd.to_string());
+                inner_ui.label(file.kind.to_string());
+                inner_ui.label(&file.target);
+                inner_ui.label(&file.description);
+                inner_ui.end_row();
+            });
+        });
+    });
+}
+
+fn process_threads_tab(ui: &mut Ui, threads: &Result<Vec<ThreadInformations>, String>, hot_color: Color32) {
+    let threads = match threads {
+        Ok(value) => value,
+        Err(e) => {
+            ui.label(RichText::new(e).weak());
+            return;
+        }
+    };
+    let running = threads.iter().filter(|x| x.state == 'R').count();
+    ui.label(format!("{} threads, {} running", threads.len(), running));
+    ui.separator();
+    ScrollArea::vertical().id_source("process_threads").show(ui, |inner_ui|{
+        Grid::new("process_threads_grid").num_columns(7).striped(true).show(inner_ui, |inner_ui|{
+            ["TID", "name", "state", "CPU %", "last CPU", "voluntary", "involuntary"].iter().for_each(|x|{
+                inner_ui.label(RichText::new(*x).weak());
+            });
+            inner_ui.end_row();
+            threads.iter().for_each(|thread|{
+                inner_ui.label(thread.tid.to_string());
+                inner_ui.label(&thread.name);
+                inner_ui.label(thread.state.to_string()).on_hover_text(thread_state_name(thread.state));
+                // a thread close to a full core is what pins a single threaded loop
+                if thread.cpu >= 90.0 {
+                    inner_ui.colored_label(hot_color, format!("{:.1}", thread.cpu));
+                } else {
+                    inner_ui.label(format!("{:.1}", thread.cpu));
+                }
+                inner_ui.label(thread.last_cpu.to_string());
+                inner_ui.label(thread.voluntary_switches.to_string())
+                    .on_hover_text("context switches waiting for something, io or a lock");
+                inner_ui.label(thread.involuntary_switches.to_string())
+                    .on_hover_text("context switches forced by the scheduler");
+                inner_ui.end_row();
+            });
+        });
+    });
+}
+
+fn process_network_tab(ui: &mut Ui, details: &ProcessDetails, connections: &Result<Vec<ConnectionInformations>, String>) {
+    let rate = details.network_plot_points.last().copied().unwrap_or(0.0);
+    ui.label(format!("tcp {} sent and received", format_rate(f64::from(rate))));
+    if !cfg!(target_os = "linux") {
+        ui.label(RichText::new("per process network is only measured on linux").weak());
+    }
+    Plot::new("process_network_plot")
+        .show_axes([false, true])
+        .height(90.0)
+        .allow_scroll(false)
+        .allow_drag(false)
+        .include_y(0.0)
+        .y_axis_formatter(|value, _range| format_rate(value))
+        .show(ui, |plot_ui|{
+            plot_ui.line(Line::new(plot_points(&details.network_plot_points)).name("tcp bytes/s"));
+        });
+    ui.separator();
+    let connections = match connections {
+        Ok(value) => value,
+        Err(e) => {
+            ui.label(RichText::new(e).weak());
+            return;
+        }
+    };
+    ScrollArea::vertical().id_source("process_connections").show(ui, |inner_ui|{
+        Grid::new("process_connections_grid").num_columns(4).striped(true).show(inner_ui, |inner_ui|{
+            ["protocol", "local", "remote", "state"].iter().for_each(|x|{
+                inner_ui.label(RichText::new(*x).weak());
+            });
+            inner_ui.end_row();
+            connections.iter().filter(|x| x.pid == Some(details.pid)).for_each(|connection|{
+                inner_ui.label(connection.protocol.to_string());
+                inner_ui.label(connection.local.to_string());
+                inner_ui.label(connection.remote.to_string());
+                inner_ui.label(connection.state);
+                inner_ui.end_row();
+            });
+        });
+    });
+}
+
+fn thread_state_name(state: char) -> &'static str {
+    match state {
+        'R' => "running",
+        'S' => "sleeping",
+        'D' => "uninterruptible disk sleep",
+        'Z' => "zombie",
+        'T' => "stopped",
+        't' => "tracing stop",
+        'X' => "dead",
+        'I' => "idle",
+        'P' => "parked",
+        _ => "unknown",
+    }
+}
+
+fn columns_definition_display(processes_sort_option: &mut ProcessesSortOption, inner_ui: &mut Ui, color: Color32) {
     let unicode_char = '⏷';
     let mut memory_char = String::from(" ");
     let mut cpu_char = String::from(" ");
     let mut disk_char = String::from(" ");
-    match mutex_data.processes_sort_option {
+    let mut network_char = String::from(" ");
+    let mut faults_char = String::from(" ");
+    match processes_sort_option {
         ProcessesSortOption::Memory => {
             memory_char = format!(" {}", unicode_char);
         }
@@ -916,57 +1892,59 @@ fn columns_definition_display(mutex_data: &mut ProcessManagerAppMutexData, inner
         ProcessesSortOption::Disk => {
             disk_char = format!(" {}", unicode_char);
         }
+        ProcessesSortOption::Network => {
+            network_char = format!(" {}", unicode_char);
+        }
+        ProcessesSortOption::MajorFaults => {
+            faults_char = format!(" {}", unicode_char);
+        }
     }
 
     inner_ui.add(Label::new(RichText::new("NAME").color(color)));
             inner_ui.add(Label::new(RichText::new(format!("CPU {}", cpu_char)).color(color))
                 .sense(Sense::click()))
                 .clicked()
-                .then(||mutex_data.processes_sort_option = ProcessesSortOption::Cpu);
+                .then(||*processes_sort_option = ProcessesSortOption::Cpu);
             inner_ui.add(Label::new(RichText::new(format!("MEMORY {}", memory_char)).color(color))
                 .sense(Sense::click()))
                 .clicked()
-                .then(||mutex_data.processes_sort_option = ProcessesSortOption::Memory);
-            inner_ui.add(Label::new(RichText::new(format!("DISK {}", disk_char)).color(color))
+                .then(||*processes_sort_option = ProcessesSortOption::Memory);
+            inner_ui.add(Label::new(RichText::new(format!("DISK KiB/s{}", disk_char)).color(color))
+                .sense(Sense::click()))
+                .on_hover_text("read + written, see \"disk I/O by process\" for the split")
+                .clicked()
+                .then(||*processes_sort_option = ProcessesSortOption::Disk);
+            inner_ui.add(Label::new(RichText::new(format!("NET KiB/s{}", network_char)).color(color))
+                .sense(Sense::click()))
+                .on_hover_text("tcp sent + received, only sampled while sorted by NET or a network tab is open")
+                .clicked()
+                .then(||*processes_sort_option = ProcessesSortOption::Network);
+            inner_ui.add(Label::new(RichText::new(format!("FAULTS {}", faults_char)).color(color))
                 .sense(Sense::click()))
+                .on_hover_text("major page faults per second")
                 .clicked()
-                .then(||mutex_data.processes_sort_option = ProcessesSortOption::Disk);
+                .then(||*processes_sort_option = ProcessesSortOption::MajorFaults);
 }
 
-pub enum ProcessesSortOption {
-    Cpu,
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ProcessDetailsTab {
     Memory,
-    Disk
+    Files,
+    Threads,
+    Network,
+    Io,
 }
 
-pub struct Data<T>{
-    data_points: usize,
-    data_records: VecDeque<T>,
-}
-
-impl<T> Data<T> {
-    pub fn new(data_points: usize) -> Self {
-        Self {
-            data_points,
-            data_records: VecDeque::with_capacity(data_points),
-        }
-    }
-
-    pub fn push(&mut self, data_record: T) {
-        self.data_records.push_back(data_record);
-        if self.data_records.len() > self.data_points + 1 {
-            self.data_records.pop_front();
-        }
-    }
-
-    pub fn data_iter(&self) -> impl Iterator<Item = &T> {
-        self.data_records.iter()
-    }
+pub enum ProcessesSortOption {
+    Cpu,
+    Memory,
+    Disk,
+    Network,
+    MajorFaults,
 }
 
 struct MemoryInformations {
     total_memory: u64,
-    total_swap: u64,
 }
 
 struct SystemInformations {
@@ -979,75 +1957,3 @@ struct CpuInformations{
     kernel_version: Option<String>,
     os_version: Option<String>,
 }
-
-struct CpuData{
-    name: String,
-    usage: f32,
-    color: Color32,
-    is_display_on_plot: bool,
-    plot_points: Option<Data<f32>>
-}
-
-impl CpuData {
-    fn new(cpus: &[Cpu], initial_auto_color_index: usize) -> Vec<Self> {
-        let mut i = initial_auto_color_index;
-        let data = cpus.iter().map(|x|{
-            let cpu = Self { name: x.name().to_string(), usage: x.cpu_usage(), is_display_on_plot: false, plot_points: None, color: Self::auto_color(i)};
-            i += 2;
-            cpu
-        }).collect();
-
-        data
-    }
-
-    fn auto_color(i: usize) -> Color32 {
-        let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0;
-        let h = i as f32 * golden_ratio;
-        Hsva::new(h, 0.85, 0.5, 1.0).into()
-    }
-}
-
-struct DiskInformations{
-    name: String,
-    mount_point: String,
-    used_space: String,
-    total_space: String,
-    kind: String,
-    file_system: String,
-    is_removable: String,
-    last_performance: Option<DISK_PERFORMANCE>,
-    plot_points: Data<f32>,
-    y_max_bound: f32,
-}
-
-struct NetworkInformations {
-    number: usize,
-    interface_name: String,
-    mac_address: MacAddr,
-    is_display_on_plot: bool,
-    network_display: Option<NetworkDisplay>,
-    total_errors_on_received: u64,
-    total_errors_on_transmitted: u64,
-}
-
-struct NetworkDisplay {
-    received_plot_points: Data<u64>,
-    transmitted_plot_points: Data<u64>,
-}
-
-struct ProcessInformations {
-    pid: Pid,
-    name: String,
-    cpu: f32,
-    memory: f32,
-    disk: f32,
-    child_processes: Vec<SecificProcess>
-}
-
-struct SecificProcess {
-    pid: Pid,
-    name: String,
-    cpu: f32,
-    memory: f32,
-    disk: f32,
-}
\ No newline at end of file
@@ -0,0 +1,557 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use egui::Color32;
+use serde::{Deserialize, Deserializer, Serialize};
+use crate::schedule::Subsystem;
+
+const CONFIG_DIR_NAME: &str = "HardwareMonitor";
+const TOML_FILE_NAME: &str = "config.toml";
+const JSON_FILE_NAME: &str = "config.json";
+
+/// Everything that used to be hard-coded in the sampler and the panels.
+/// Missing keys fall back to the defaults below, unknown keys are rejected so typos don't go unnoticed.
+#[derive(Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub sampling: SamplingConfig,
+    pub panels: PanelsConfig,
+    pub plots: PlotsConfig,
+    pub colors: ColorsConfig,
+    pub thresholds: ThresholdsConfig,
+    /// Reachability targets measured by the probe thread, none by default.
+    pub probes: Vec<ProbeConfig>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SamplingConfig {
+    /// Used by every subsystem without its own interval below.
+    pub interval_ms: u64,
+    pub history_length: usize,
+    pub cpu_interval_ms: Option<u64>,
+    pub memory_interval_ms: Option<u64>,
+    pub processes_interval_ms: Option<u64>,
+    pub disk_io_interval_ms: Option<u64>,
+    pub disk_space_interval_ms: Option<u64>,
+    pub network_interval_ms: Option<u64>,
+    pub sensors_interval_ms: Option<u64>,
+    pub connections_interval_ms: Option<u64>,
+    /// How long an unplugged disk, interface or cpu stays listed with its history.
+    pub removed_device_retention_ms: u64,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PanelsConfig {
+    pub cpu: bool,
+    pub network: bool,
+    pub memory: bool,
+    pub disks: bool,
+    pub processes: bool,
+    pub sensors: bool,
+    pub connections: bool,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlotsConfig {
+    pub disk_y_min_bound: f32,
+    pub network_y_min_bound: u64,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorsConfig {
+    pub accent: ConfigColor,
+    pub cpu_usage: ConfigColor,
+    pub memory_usage: ConfigColor,
+    pub swap_usage: ConfigColor,
+    pub selected_process: ConfigColor,
+    /// Disk reads, writes are drawn in `disk_write`.
+    pub disk_transfer: ConfigColor,
+    pub disk_write: ConfigColor,
+    pub alert: ConfigColor,
+}
+
+/// Readings past these are highlighted. A temperature at or above the sensor's own critical value
+/// is highlighted regardless.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThresholdsConfig {
+    pub temperature_celsius: f32,
+    /// A fan spinning slower than this is highlighted, e.g. a stalled pump.
+    pub fan_min_rpm: Option<f32>,
+    pub power_watts: Option<f32>,
+    /// Swap in plus swap out rate, in pages per second, past which the memory panel warns about thrashing.
+    pub swap_pages_per_second: f32,
+    /// A filesystem projected to fill up within this many hours, at its growth over the history window, is highlighted.
+    pub disk_full_hours: f32,
+}
+
+/// One `[[probes]]` entry. `target` is `host:port` for tcp and a host for icmp.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ProbeConfig {
+    /// Shown in the panel, the target when missing.
+    pub name: Option<String>,
+    pub kind: ProbeKind,
+    pub target: String,
+    #[serde(default = "default_probe_interval_ms")]
+    pub interval_ms: u64,
+    #[serde(default = "default_probe_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl ProbeConfig {
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.target)
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeKind {
+    /// Time to complete a tcp handshake.
+    Tcp,
+    /// Echo request round trip, needs ping sockets (`net.ipv4.ping_group_range`) on linux.
+    Icmp,
+}
+
+impl fmt::Display for ProbeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeKind::Tcp => write!(f, "tcp"),
+            ProbeKind::Icmp => write!(f, "icmp"),
+        }
+    }
+}
+
+fn default_probe_interval_ms() -> u64 {
+    5000
+}
+
+fn default_probe_timeout_ms() -> u64 {
+    1000
+}
+
+/// `#RRGGBB` color from the config file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfigColor(pub Color32);
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 1000,
+            history_length: 20,
+            cpu_interval_ms: None,
+            memory_interval_ms: None,
+            processes_interval_ms: None,
+            disk_io_interval_ms: None,
+            disk_space_interval_ms: None,
+            network_interval_ms: None,
+            sensors_interval_ms: None,
+            connections_interval_ms: None,
+            removed_device_retention_ms: 60_000,
+        }
+    }
+}
+
+impl Default for PanelsConfig {
+    fn default() -> Self {
+        Self {
+            cpu: true,
+            network: true,
+            memory: true,
+            disks: true,
+            processes: true,
+            sensors: true,
+            connections: true,
+        }
+    }
+}
+
+impl Default for PlotsConfig {
+    fn default() -> Self {
+        Self {
+            disk_y_min_bound: 450.0,
+            network_y_min_bound: 100,
+        }
+    }
+}
+
+impl Default for ColorsConfig {
+    fn default() -> Self {
+        Self {
+            accent: ConfigColor(Color32::from_rgb(210, 151, 49)),
+            cpu_usage: ConfigColor(Color32::RED),
+            memory_usage: ConfigColor(Color32::RED),
+            swap_usage: ConfigColor(Color32::LIGHT_BLUE),
+            selected_process: ConfigColor(Color32::LIGHT_BLUE),
+            disk_transfer: ConfigColor(Color32::GREEN),
+            disk_write: ConfigColor(Color32::from_rgb(255, 165, 0)),
+            alert: ConfigColor(Color32::LIGHT_RED),
+        }
+    }
+}
+
+impl Default for ThresholdsConfig {
+    fn default() -> Self {
+        Self {
+            temperature_celsius: 85.0,
+            fan_min_rpm: None,
+            power_watts: None,
+            swap_pages_per_second: 256.0,
+            disk_full_hours: 24.0,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ConfigColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse_hex_color(&value)
+            .map(ConfigColor)
+            .ok_or_else(|| serde::de::Error::custom(format!("expected a color in #RRGGBB form, got \"{}\"", value)))
+    }
+}
+
+fn parse_hex_color(value: &str) -> Option<Color32> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color32::from_rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, message: String },
+    Invalid { path: PathBuf, problems: Vec<String> },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "cannot read {}: {}", path.display(), source)
+            }
+            ConfigError::Parse { path, message } => {
+                write!(f, "{} is malformed: {}", path.display(), message)
+            }
+            ConfigError::Invalid { path, problems } => {
+                write!(f, "{} is invalid: {}", path.display(), problems.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/HardwareMonitor` (or `~/.config/HardwareMonitor`), `%APPDATA%\HardwareMonitor` on Windows.
+    pub fn config_dir() -> Option<PathBuf> {
+        let base = if cfg!(windows) {
+            std::env::var_os("APPDATA").map(PathBuf::from)
+        } else {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        };
+        base.map(|dir| dir.join(CONFIG_DIR_NAME))
+    }
+
+    /// `config.toml` wins over `config.json` when both exist.
+    pub fn find_config_file() -> Option<PathBuf> {
+        let dir = Self::config_dir()?;
+        [TOML_FILE_NAME, JSON_FILE_NAME]
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    }
+
+    /// Loads the config file if there is one, defaults otherwise.
+    pub fn load() -> Result<Self, ConfigError> {
+        match Self::find_config_file() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let is_json = path.extension().map_or(false, |extension| extension == "json");
+        let parsed: Result<Config, String> = if is_json {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&content).map_err(|e| e.to_string())
+        };
+        let config = parsed.map_err(|message| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message,
+        })?;
+
+        let problems = config.validate();
+        if !problems.is_empty() {
+            return Err(ConfigError::Invalid {
+                path: path.to_path_buf(),
+                problems,
+            });
+        }
+        Ok(config)
+    }
+
+    /// Checks the ranges serde can't express. Returns one message per offending key.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !(50..=60_000).contains(&self.sampling.interval_ms) {
+            problems.push(format!("sampling.interval_ms must be between 50 and 60000, got {}", self.sampling.interval_ms));
+        }
+        let subsystem_intervals = [
+            ("sampling.cpu_interval_ms", self.sampling.cpu_interval_ms),
+            ("sampling.memory_interval_ms", self.sampling.memory_interval_ms),
+            ("sampling.processes_interval_ms", self.sampling.processes_interval_ms),
+            ("sampling.disk_io_interval_ms", self.sampling.disk_io_interval_ms),
+            ("sampling.disk_space_interval_ms", self.sampling.disk_space_interval_ms),
+            ("sampling.network_interval_ms", self.sampling.network_interval_ms),
+            ("sampling.sensors_interval_ms", self.sampling.sensors_interval_ms),
+            ("sampling.connections_interval_ms", self.sampling.connections_interval_ms),
+        ];
+        for (key, interval_ms) in subsystem_intervals {
+            if let Some(interval_ms) = interval_ms {
+                if !(50..=3_600_000).contains(&interval_ms) {
+                    problems.push(format!("{} must be between 50 and 3600000, got {}", key, interval_ms));
+                }
+            }
+        }
+        if self.sampling.removed_device_retention_ms > 3_600_000 {
+            problems.push(format!("sampling.removed_device_retention_ms must be at most 3600000, got {}", self.sampling.removed_device_retention_ms));
+        }
+        if !(2..=3600).contains(&self.sampling.history_length) {
+            problems.push(format!("sampling.history_length must be between 2 and 3600, got {}", self.sampling.history_length));
+        }
+        if !self.plots.disk_y_min_bound.is_finite() || self.plots.disk_y_min_bound <= 0.0 {
+            problems.push(format!("plots.disk_y_min_bound must be a positive number, got {}", self.plots.disk_y_min_bound));
+        }
+        if self.plots.network_y_min_bound == 0 {
+            problems.push(String::from("plots.network_y_min_bound must be greater than 0"));
+        }
+        for (index, probe) in self.probes.iter().enumerate() {
+            let key = format!("probes[{}]", index);
+            if !(100..=3_600_000).contains(&probe.interval_ms) {
+                problems.push(format!("{}.interval_ms must be between 100 and 3600000, got {}", key, probe.interval_ms));
+            }
+            if !(10..=60_000).contains(&probe.timeout_ms) {
+                problems.push(format!("{}.timeout_ms must be between 10 and 60000, got {}", key, probe.timeout_ms));
+            }
+            let has_port = probe.target.rsplit_once(':').map_or(false, |(_, port)| port.parse::<u16>().is_ok());
+            if probe.kind == ProbeKind::Tcp && !has_port {
+                problems.push(format!("{}.target of a tcp probe must be host:port, got \"{}\"", key, probe.target));
+            }
+            if self.probes[..index].iter().any(|x| x.name() == probe.name()) {
+                problems.push(format!("{}.name \"{}\" is used by another probe", key, probe.name()));
+            }
+        }
+        let thresholds = [
+            ("thresholds.temperature_celsius", Some(self.thresholds.temperature_celsius)),
+            ("thresholds.fan_min_rpm", self.thresholds.fan_min_rpm),
+            ("thresholds.power_watts", self.thresholds.power_watts),
+            ("thresholds.swap_pages_per_second", Some(self.thresholds.swap_pages_per_second)),
+            ("thresholds.disk_full_hours", Some(self.thresholds.disk_full_hours)),
+        ];
+        for (key, threshold) in thresholds {
+            if let Some(threshold) = threshold {
+                if !threshold.is_finite() || threshold <= 0.0 {
+                    problems.push(format!("{} must be a positive number, got {}", key, threshold));
+                }
+            }
+        }
+        problems
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.sampling.interval_ms)
+    }
+
+    pub fn removed_device_retention(&self) -> Duration {
+        Duration::from_millis(self.sampling.removed_device_retention_ms)
+    }
+
+    pub fn refresh_interval(&self, subsystem: Subsystem) -> Duration {
+        let interval_ms = match subsystem {
+            Subsystem::Cpu => self.sampling.cpu_interval_ms,
+            Subsystem::Memory => self.sampling.memory_interval_ms,
+            Subsystem::Processes | Subsystem::ProcessIo | Subsystem::ProcessNetwork => self.sampling.processes_interval_ms,
+            Subsystem::DiskIo | Subsystem::BlockDevices => self.sampling.disk_io_interval_ms,
+            Subsystem::DiskSpace => self.sampling.disk_space_interval_ms,
+            Subsystem::Network => self.sampling.network_interval_ms,
+            Subsystem::Sensors => self.sampling.sensors_interval_ms,
+            Subsystem::Connections => self.sampling.connections_interval_ms,
+        };
+        Duration::from_millis(interval_ms.unwrap_or(self.sampling.interval_ms))
+    }
+
+    /// Subsystems are only sampled while the panel showing them is visible.
+    pub fn is_sampled(&self, subsystem: Subsystem) -> bool {
+        match subsystem {
+            Subsystem::Cpu => self.panels.cpu,
+            Subsystem::Memory => self.panels.memory,
+            Subsystem::Processes | Subsystem::ProcessIo | Subsystem::ProcessNetwork => self.panels.processes,
+            Subsystem::DiskIo | Subsystem::BlockDevices | Subsystem::DiskSpace => self.panels.disks,
+            Subsystem::Network => self.panels.network,
+            Subsystem::Sensors => self.panels.sensors,
+            Subsystem::Connections => self.panels.connections,
+        }
+    }
+}
+
+/// Remembers which file was loaded and when it was last modified, so the watcher thread can reload it on change.
+pub struct ConfigWatcher {
+    path: Option<PathBuf>,
+    last_modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    pub fn new() -> Self {
+        let path = Config::find_config_file();
+        let last_modified = path.as_deref().and_then(modified_time);
+        Self { path, last_modified }
+    }
+
+    /// Returns `Some` when the config file appeared, changed or disappeared since the last poll.
+    pub fn poll(&mut self) -> Option<Result<Config, ConfigError>> {
+        let path = Config::find_config_file();
+        let last_modified = path.as_deref().and_then(modified_time);
+        if path == self.path && last_modified == self.last_modified {
+            return None;
+        }
+        self.path = path;
+        self.last_modified = last_modified;
+
+        match &self.path {
+            Some(path) => Some(Config::load_from(path)),
+            None => Some(Ok(Config::default())),
+        }
+    }
+}
+
+impl Default for ConfigWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every key of the README with its default value written out.
+    const DEFAULTS_TOML: &str = r##"
+[sampling]
+interval_ms = 1000
+history_length = 20
+removed_device_retention_ms = 60000
+
+[panels]
+cpu = true
+network = true
+memory = true
+disks = true
+processes = true
+sensors = true
+connections = true
+
+[plots]
+disk_y_min_bound = 450.0
+network_y_min_bound = 100
+
+[colors]
+accent = "#D29731"
+cpu_usage = "#FF0000"
+memory_usage = "#FF0000"
+swap_usage = "#ADD8E6"
+selected_process = "#ADD8E6"
+disk_transfer = "#00FF00"
+disk_write = "#FFA500"
+alert = "#FF8080"
+
+[thresholds]
+temperature_celsius = 85.0
+swap_pages_per_second = 256.0
+disk_full_hours = 24.0
+"##;
+
+    fn load(file_name: &str, content: &str) -> Result<Config, ConfigError> {
+        let path = std::env::temp_dir().join(format!("hardware-monitor-{}-{}", std::process::id(), file_name));
+        fs::write(&path, content).unwrap();
+        let config = Config::load_from(&path);
+        fs::remove_file(&path).unwrap();
+        config
+    }
+
+    #[test]
+    fn the_documented_defaults_load_as_the_default_config() {
+        assert!(Config::default().validate().is_empty());
+        assert_eq!(load("defaults.toml", DEFAULTS_TOML).unwrap(), Config::default());
+        assert_eq!(load("empty.toml", "").unwrap(), Config::default());
+        assert_eq!(load("empty.json", "{}").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn an_unknown_key_is_rejected() {
+        let error = load("unknown.toml", "[sampling]\ninterval = 500\n").unwrap_err();
+
+        assert!(matches!(&error, ConfigError::Parse { message, .. } if message.contains("interval")), "{}", error);
+    }
+
+    #[test]
+    fn a_malformed_color_is_rejected() {
+        ["red", "#12345", "#GGGGGG", "123456"].iter().for_each(|color|{
+            let error = load("color.toml", &format!("[colors]\naccent = \"{}\"\n", color)).unwrap_err();
+
+            assert!(matches!(&error, ConfigError::Parse { message, .. } if message.contains("#RRGGBB")), "{}", error);
+        });
+        assert_eq!(parse_hex_color("#d29731"), Some(Color32::from_rgb(210, 151, 49)));
+    }
+
+    #[test]
+    fn out_of_range_values_are_reported_per_key() {
+        let error = load("ranges.toml", "[sampling]\ninterval_ms = 10\ncpu_interval_ms = 4000000\n\n[thresholds]\ntemperature_celsius = -1.0\n").unwrap_err();
+
+        match error {
+            ConfigError::Invalid { problems, .. } => {
+                assert_eq!(problems.len(), 3, "{:?}", problems);
+                assert!(problems[0].starts_with("sampling.interval_ms"));
+                assert!(problems[1].starts_with("sampling.cpu_interval_ms"));
+                assert!(problems[2].starts_with("thresholds.temperature_celsius"));
+            }
+            other => panic!("expected range problems, got {}", other),
+        }
+    }
+
+    #[test]
+    fn a_missing_file_is_an_error_for_the_caller_to_fall_back_on() {
+        let path = std::env::temp_dir().join(format!("hardware-monitor-{}-missing.toml", std::process::id()));
+
+        assert!(matches!(Config::load_from(&path), Err(ConfigError::Io { .. })));
+    }
+}
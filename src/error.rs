@@ -0,0 +1,177 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::SystemTime;
+use sysinfo::Pid;
+
+/// How many distinct problems the log keeps before dropping the oldest.
+const ERROR_LOG_CAPACITY: usize = 50;
+
+/// Everything that can go wrong while collecting data. None of these stop the sampler,
+/// the affected value is skipped or replaced with a fallback and the error lands in the `ErrorLog`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MonitorError {
+    InvalidFileSystemName { mount_point: String },
+    PhysicalCoreCountUnavailable,
+    #[cfg_attr(not(windows), allow(dead_code))]
+    DiskOpenFailed { disk: String },
+    DiskPerformanceUnavailable { disk: String },
+    ProcessNotFound { pid: Pid },
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    ProcFileUnavailable { path: String },
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    SocketDiagUnavailable { reason: String },
+    IoPriorityNotSet { pid: Pid, reason: String },
+}
+
+impl fmt::Display for MonitorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorError::InvalidFileSystemName { mount_point } => {
+                write!(f, "file system name of {} is not valid UTF-8", mount_point)
+            }
+            MonitorError::PhysicalCoreCountUnavailable => {
+                write!(f, "physical core count unavailable, process cpu is divided by the logical core count")
+            }
+            MonitorError::DiskOpenFailed { disk } => {
+                write!(f, "cannot open disk {}", disk)
+            }
+            MonitorError::DiskPerformanceUnavailable { disk } => {
+                write!(f, "performance counters of disk {} are unavailable", disk)
+            }
+            MonitorError::ProcessNotFound { pid } => {
+                write!(f, "process {} is gone", pid)
+            }
+            MonitorError::ProcFileUnavailable { path } => {
+                write!(f, "cannot read {}", path)
+            }
+            MonitorError::SocketDiagUnavailable { reason } => {
+                write!(f, "per process network is unavailable, sock_diag failed: {}", reason)
+            }
+            MonitorError::IoPriorityNotSet { pid, reason } => {
+                write!(f, "cannot change the I/O priority of process {}: {}", pid, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MonitorError {}
+
+impl MonitorError {
+    pub fn severity(&self) -> Severity {
+        match self {
+            MonitorError::InvalidFileSystemName { .. }
+            | MonitorError::PhysicalCoreCountUnavailable
+            | MonitorError::ProcessNotFound { .. }
+            | MonitorError::SocketDiagUnavailable { .. }
+            | MonitorError::IoPriorityNotSet { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct LoggedError {
+    pub error: MonitorError,
+    pub first_seen: SystemTime,
+    pub last_seen: SystemTime,
+    pub count: u32,
+}
+
+/// Recent collection failures, newest last. A failure repeating every tick is folded into one entry.
+#[derive(Clone, Debug, Default)]
+pub struct ErrorLog {
+    entries: VecDeque<LoggedError>,
+}
+
+impl ErrorLog {
+    pub fn push(&mut self, error: MonitorError) {
+        let now = SystemTime::now();
+        if let Some(position) = self.entries.iter().position(|entry| entry.error == error) {
+            if let Some(mut entry) = self.entries.remove(position) {
+                entry.last_seen = now;
+                entry.count = entry.count.saturating_add(1);
+                self.entries.push_back(entry);
+            }
+            return;
+        }
+
+        self.entries.push_back(LoggedError {
+            error,
+            first_seen: now,
+            last_seen: now,
+            count: 1,
+        });
+        if self.entries.len() > ERROR_LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &LoggedError> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk_error(disk: &str) -> MonitorError {
+        MonitorError::DiskPerformanceUnavailable { disk: disk.to_string() }
+    }
+
+    #[test]
+    fn a_repeated_error_is_folded_into_one_entry_with_a_count() {
+        let mut log = ErrorLog::default();
+        log.push(disk_error("sda"));
+        log.push(MonitorError::PhysicalCoreCountUnavailable);
+        log.push(disk_error("sda"));
+        log.push(disk_error("sda"));
+
+        assert_eq!(log.len(), 2);
+        let newest = log.entries().last().unwrap();
+        assert_eq!((&newest.error, newest.count), (&disk_error("sda"), 3));
+        assert!(newest.first_seen <= newest.last_seen);
+        assert_eq!(log.entries().next().unwrap().count, 1);
+    }
+
+    #[test]
+    fn errors_that_differ_in_their_details_are_kept_apart() {
+        let mut log = ErrorLog::default();
+        log.push(disk_error("sda"));
+        log.push(disk_error("sdb"));
+
+        assert_eq!(log.len(), 2);
+    }
+
+    #[test]
+    fn the_log_drops_its_oldest_entry_when_full() {
+        let mut log = ErrorLog::default();
+        (0..ERROR_LOG_CAPACITY + 5).for_each(|x| log.push(disk_error(&format!("disk{}", x))));
+
+        assert_eq!(log.len(), ERROR_LOG_CAPACITY);
+        assert_eq!(log.entries().next().unwrap().error, disk_error("disk5"));
+
+        // a repeat of a kept entry doesn't push anything out
+        log.push(disk_error("disk5"));
+        assert_eq!(log.len(), ERROR_LOG_CAPACITY);
+        assert_eq!(log.entries().next().unwrap().error, disk_error("disk6"));
+    }
+}
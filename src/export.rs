@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use crate::config::{Config, ProbeKind};
+use crate::snapshot::Snapshot;
+
+const SNAPSHOTS_DIR_NAME: &str = "snapshots";
+
+/// The part of a snapshot worth keeping outside the monitor: current usage, interface rates and probe results.
+#[derive(Serialize)]
+struct SnapshotExport {
+    /// Seconds since the unix epoch.
+    taken_at: u64,
+    cpu_usage_percent: u64,
+    memory_used_bytes: u64,
+    swap_used_bytes: u64,
+    networks: Vec<NetworkExport>,
+    probes: Vec<ProbeExport>,
+}
+
+#[derive(Serialize)]
+struct NetworkExport {
+    interface_name: String,
+    received_bytes_per_second: f64,
+    transmitted_bytes_per_second: f64,
+    received_since_start: u64,
+    transmitted_since_start: u64,
+}
+
+#[derive(Serialize)]
+struct ProbeExport {
+    name: String,
+    kind: ProbeKind,
+    target: String,
+    sent: u64,
+    lost: u64,
+    loss_percent: f32,
+    last_error: Option<String>,
+    /// Oldest first, `null` for a lost attempt.
+    round_trips_ms: Vec<Option<f32>>,
+}
+
+/// Writes `snapshot` as pretty printed JSON to `snapshots/snapshot-<unix time>.json` in the config directory
+/// and returns the file's path.
+pub fn export_snapshot(snapshot: &Snapshot) -> Result<PathBuf, String> {
+    let dir = Config::config_dir()
+        .ok_or_else(|| String::from("no config directory to export to"))?
+        .join(SNAPSHOTS_DIR_NAME);
+    fs::create_dir_all(&dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+
+    let taken_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let export = SnapshotExport {
+        taken_at,
+        cpu_usage_percent: snapshot.total_cpu_usage,
+        memory_used_bytes: snapshot.memory_usage,
+        swap_used_bytes: snapshot.swap_usage,
+        networks: snapshot.network_informations.iter()
+            .filter(|x| x.removed_since.is_none())
+            .map(|x| NetworkExport {
+                interface_name: x.interface_name.clone(),
+                received_bytes_per_second: x.network_display.received_plot_points.last().copied().unwrap_or(0.0),
+                transmitted_bytes_per_second: x.network_display.transmitted_plot_points.last().copied().unwrap_or(0.0),
+                received_since_start: x.totals.received,
+                transmitted_since_start: x.totals.transmitted,
+            })
+            .collect(),
+        probes: snapshot.probes.iter()
+            .map(|x| ProbeExport {
+                name: x.name.clone(),
+                kind: x.kind,
+                target: x.target.clone(),
+                sent: x.sent,
+                lost: x.lost,
+                loss_percent: x.loss_percent(),
+                last_error: x.last_error.clone(),
+                round_trips_ms: x.round_trips.data_iter().copied().collect(),
+            })
+            .collect(),
+    };
+
+    let content = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("snapshot-{}.json", taken_at));
+    fs::write(&path, content).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+    Ok(path)
+}
@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+/// A disk, network interface or cpu that can appear and disappear while the monitor is running.
+pub trait Device {
+    type Id: PartialEq;
+
+    fn id(&self) -> &Self::Id;
+    fn removed_since(&self) -> Option<Instant>;
+    fn set_removed_since(&mut self, removed_since: Option<Instant>);
+}
+
+/// Brings `devices` in line with what the system reports right now.
+/// New devices are appended with a fresh history, missing ones are marked as removed and kept for
+/// `retention` so their history stays visible, and a device that comes back within it picks up its old history.
+pub fn reconcile<D, S>(
+    devices: &mut Vec<D>,
+    present: &[S],
+    id_of: impl Fn(&S) -> D::Id,
+    mut create: impl FnMut(&S) -> D,
+    now: Instant,
+    retention: Duration,
+) where
+    D: Device,
+{
+    let present_ids: Vec<D::Id> = present.iter().map(&id_of).collect();
+
+    devices.retain_mut(|device| {
+        let is_present = present_ids.contains(device.id());
+        match (is_present, device.removed_since()) {
+            // back too late, it starts over with a fresh history
+            (true, Some(removed_since)) if now.duration_since(removed_since) >= retention => return false,
+            (true, Some(_)) => device.set_removed_since(None),
+            (false, None) => device.set_removed_since(Some(now)),
+            _ => {}
+        }
+        device.removed_since().map_or(true, |removed_since| now.duration_since(removed_since) < retention)
+    });
+
+    present.iter().zip(present_ids.iter()).for_each(|(source, id)| {
+        if !devices.iter().any(|device| device.id() == id) {
+            devices.push(create(source));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RETENTION: Duration = Duration::from_secs(60);
+
+    /// A device whose history is how many times it was seen.
+    #[derive(Debug, PartialEq)]
+    struct FakeDevice {
+        id: &'static str,
+        history: Vec<u32>,
+        removed_since: Option<Instant>,
+    }
+
+    impl Device for FakeDevice {
+        type Id = &'static str;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+
+        fn removed_since(&self) -> Option<Instant> {
+            self.removed_since
+        }
+
+        fn set_removed_since(&mut self, removed_since: Option<Instant>) {
+            self.removed_since = removed_since;
+        }
+    }
+
+    fn reconcile_at(devices: &mut Vec<FakeDevice>, present: &[&'static str], now: Instant) {
+        reconcile(devices, present, |x| *x, |x| FakeDevice { id: x, history: Vec::new(), removed_since: None }, now, RETENTION);
+        devices.iter_mut().filter(|x| x.removed_since.is_none()).for_each(|x| x.history.push(1));
+    }
+
+    fn ids(devices: &[FakeDevice]) -> Vec<&'static str> {
+        devices.iter().map(|x| x.id).collect()
+    }
+
+    #[test]
+    fn new_devices_are_appended() {
+        let now = Instant::now();
+        let mut devices = Vec::new();
+
+        reconcile_at(&mut devices, &["sda"], now);
+        reconcile_at(&mut devices, &["sdb", "sda"], now + Duration::from_secs(1));
+
+        assert_eq!(ids(&devices), ["sda", "sdb"]);
+        assert_eq!(devices[0].history.len(), 2);
+        assert!(devices.iter().all(|x| x.removed_since.is_none()));
+    }
+
+    #[test]
+    fn a_missing_device_is_kept_for_the_retention_period_then_dropped() {
+        let now = Instant::now();
+        let mut devices = Vec::new();
+        reconcile_at(&mut devices, &["sda", "sdb"], now);
+
+        let unplugged_at = now + Duration::from_secs(1);
+        reconcile_at(&mut devices, &["sda"], unplugged_at);
+        assert_eq!(ids(&devices), ["sda", "sdb"]);
+        assert_eq!(devices[1].removed_since, Some(unplugged_at));
+
+        reconcile_at(&mut devices, &["sda"], unplugged_at + RETENTION - Duration::from_millis(1));
+        assert_eq!(ids(&devices), ["sda", "sdb"]);
+        assert_eq!(devices[1].removed_since, Some(unplugged_at));
+
+        reconcile_at(&mut devices, &["sda"], unplugged_at + RETENTION);
+        assert_eq!(ids(&devices), ["sda"]);
+    }
+
+    #[test]
+    fn a_device_back_within_the_retention_period_keeps_its_history() {
+        let now = Instant::now();
+        let mut devices = Vec::new();
+        reconcile_at(&mut devices, &["eth0"], now);
+        reconcile_at(&mut devices, &[], now + Duration::from_secs(1));
+
+        reconcile_at(&mut devices, &["eth0"], now + Duration::from_secs(2));
+
+        assert_eq!(devices, [FakeDevice { id: "eth0", history: vec![1, 1], removed_since: None }]);
+    }
+
+    #[test]
+    fn a_device_back_after_the_retention_period_starts_over() {
+        let now = Instant::now();
+        let mut devices = Vec::new();
+        reconcile_at(&mut devices, &["eth0"], now);
+        reconcile_at(&mut devices, &[], now + Duration::from_secs(1));
+
+        reconcile_at(&mut devices, &["eth0"], now + Duration::from_secs(1) + RETENTION);
+
+        assert_eq!(devices, [FakeDevice { id: "eth0", history: vec![1], removed_since: None }]);
+    }
+}
@@ -1,4 +1,17 @@
 #![warn(clippy::all, rust_2018_idioms)]
+#![allow(non_snake_case)] // the crate keeps its ProcessManager name
 
 mod app;
+mod config;
+mod error;
+mod export;
+mod hotplug;
+#[cfg(target_os = "linux")]
+mod linux;
+mod probe;
+mod sampler;
+mod schedule;
+mod snapshot;
+#[cfg(windows)]
+mod windows;
 pub use app::ProcessManagerApp;
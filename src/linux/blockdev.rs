@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::linux::diskstats::{self, SECTOR_SIZE};
+use crate::snapshot::{BlockDevice, BlockDeviceKind, DiskCounters};
+
+const SYS_BLOCK: &str = "/sys/block";
+const SYS_CLASS_BLOCK: &str = "/sys/class/block";
+const PROC_MOUNTINFO: &str = "/proc/self/mountinfo";
+/// Deep enough for lvm on crypt on raid on a partition, and a stop should `holders` ever loop.
+const MAX_DEPTH: usize = 8;
+
+/// What the tree is built from: every node with its size, and the mount table.
+/// Reading it is cheap next to walking the tree, which only needs to be done again when it changes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TopologyStamp {
+    devices: Vec<(String, Option<String>)>,
+    mountinfo: String,
+}
+
+pub fn stamp() -> TopologyStamp {
+    TopologyStamp {
+        devices: entries(Path::new(SYS_CLASS_BLOCK)).into_iter().map(|name|{
+            let size = read_value(&sysfs_dir(&name).join("size"));
+            (name, size)
+        }).collect(),
+        mountinfo: fs::read_to_string(PROC_MOUNTINFO).unwrap_or_default(),
+    }
+}
+
+/// Every device under `/sys/block` that isn't stacked on another one, with its partitions
+/// and whatever `holders` stacks on top of them, down to the mount points.
+pub fn topology() -> io::Result<Vec<BlockDevice>> {
+    let mounts = mount_points();
+    let mut names: Vec<String> = fs::read_dir(SYS_BLOCK)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    Ok(names.iter()
+        .filter(|name| !has_entries(&sysfs_dir(name).join("slaves")))
+        .filter_map(|name| device(name, &mounts, 0))
+        .collect())
+}
+
+/// Counters of one node of the tree, partitions and stacked devices keep them too.
+pub fn counters(name: &str) -> Option<DiskCounters> {
+    let content = fs::read_to_string(sysfs_dir(name).join("stat")).ok()?;
+    diskstats::parse_counters(&content.split_whitespace().collect::<Vec<&str>>())
+}
+
+fn device(name: &str, mounts: &HashMap<String, Vec<PathBuf>>, depth: usize) -> Option<BlockDevice> {
+    let dir = sysfs_dir(name);
+    let size = read_value(&dir.join("size"))?.parse::<u64>().ok()? * SECTOR_SIZE;
+    // unused loop devices and empty card readers
+    if size == 0 && depth == 0 {
+        return None;
+    }
+
+    let mut children: Vec<BlockDevice> = Vec::new();
+    if depth < MAX_DEPTH {
+        let mut partitions = entries(&dir);
+        partitions.retain(|x| x.starts_with(name) && dir.join(x).join("partition").exists());
+        let holders = entries(&dir.join("holders"));
+        children = partitions.iter().chain(holders.iter())
+            .filter_map(|x| device(x, mounts, depth + 1))
+            .collect();
+    }
+
+    Some(BlockDevice {
+        name: name.to_string(),
+        label: read_value(&dir.join("dm/name")),
+        kind: kind(name, &dir),
+        size,
+        model: read_value(&dir.join("device/model")),
+        serial: ["serial", "device/serial", "device/wwid"].iter().find_map(|x| read_value(&dir.join(x))),
+        rotational: read_value(&dir.join("queue/rotational")).map(|x| x == "1"),
+        scheduler: read_value(&dir.join("queue/scheduler")).as_deref().and_then(active_scheduler),
+        mount_points: read_value(&dir.join("dev")).and_then(|x| mounts.get(&x).cloned()).unwrap_or_default(),
+        io: None,
+        children,
+    })
+}
+
+fn kind(name: &str, dir: &Path) -> BlockDeviceKind {
+    if dir.join("partition").exists() {
+        return BlockDeviceKind::Partition;
+    }
+    if let Some(uuid) = read_value(&dir.join("dm/uuid")) {
+        return device_mapper_kind(&uuid);
+    }
+    if let Some(level) = read_value(&dir.join("md/level")) {
+        return BlockDeviceKind::Raid(level);
+    }
+    if name.starts_with("loop") {
+        BlockDeviceKind::Loop
+    } else {
+        BlockDeviceKind::Disk
+    }
+}
+
+/// The target type leads the dm uuid, `LVM-<vg uuid><lv uuid>` or `CRYPT-LUKS2-<uuid>-<name>`.
+fn device_mapper_kind(uuid: &str) -> BlockDeviceKind {
+    match uuid.split('-').next() {
+        Some("LVM") => BlockDeviceKind::Lvm,
+        Some("CRYPT") => BlockDeviceKind::Crypt,
+        _ => BlockDeviceKind::DeviceMapper,
+    }
+}
+
+/// `queue/scheduler` lists the available schedulers with the active one in brackets,
+/// `mq-deadline kyber [bfq] none`. Devices without a queue print only `none`.
+fn active_scheduler(schedulers: &str) -> Option<String> {
+    let start = schedulers.find('[')?;
+    let end = schedulers.find(']')?;
+    schedulers.get(start + 1..end).map(str::to_string)
+}
+
+/// Mount points by `major:minor`, the way `/sys/class/block/<device>/dev` names the device.
+fn mount_points() -> HashMap<String, Vec<PathBuf>> {
+    let mut mounts: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    let content = fs::read_to_string(PROC_MOUNTINFO).unwrap_or_default();
+    content.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+        .filter(|fields| fields.len() > 4)
+        .for_each(|fields| mounts.entry(fields[2].to_string()).or_default().push(PathBuf::from(unescape(fields[4]))));
+    mounts
+}
+
+/// mountinfo writes spaces, tabs, newlines and backslashes in paths as `\ooo` octal escapes.
+fn unescape(path: &str) -> String {
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = tail.get(..3)
+            .filter(|digits| byte == b'\\' && digits.iter().all(|x| (b'0'..=b'7').contains(x)))
+            .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+        match escaped {
+            Some(value) => {
+                bytes.push(value);
+                rest = &tail[3..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn sysfs_dir(name: &str) -> PathBuf {
+    Path::new(SYS_CLASS_BLOCK).join(name)
+}
+
+fn read_value(path: &Path) -> Option<String> {
+    let value = fs::read_to_string(path).ok()?.trim().to_string();
+    Some(value).filter(|x| !x.is_empty())
+}
+
+fn entries(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| entry.file_name().to_string_lossy().into_owned()).collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+    names
+}
+
+fn has_entries(dir: &Path) -> bool {
+    fs::read_dir(dir).map(|mut entries| entries.next().is_some()).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn octal_escapes_of_mountinfo_are_decoded() {
+        assert_eq!(unescape("/media/usb\\040stick"), "/media/usb stick");
+        assert_eq!(unescape("/mnt/tab\\011and\\012newline"), "/mnt/tab\tand\nnewline");
+        assert_eq!(unescape("/mnt/back\\134slash"), "/mnt/back\\slash");
+        assert_eq!(unescape("/"), "/");
+    }
+
+    #[test]
+    fn a_backslash_without_three_octal_digits_is_kept() {
+        assert_eq!(unescape("/mnt/a\\09b"), "/mnt/a\\09b");
+        assert_eq!(unescape("/mnt/end\\04"), "/mnt/end\\04");
+        assert_eq!(unescape("/mnt/end\\"), "/mnt/end\\");
+    }
+
+    #[test]
+    fn the_active_scheduler_is_the_one_in_brackets() {
+        assert_eq!(active_scheduler("[mq-deadline] kyber bfq none").as_deref(), Some("mq-deadline"));
+        assert_eq!(active_scheduler("mq-deadline kyber [bfq] none").as_deref(), Some("bfq"));
+        assert_eq!(active_scheduler("[none] mq-deadline").as_deref(), Some("none"));
+        assert_eq!(active_scheduler("none"), None);
+    }
+
+    #[test]
+    fn device_mapper_targets_are_told_apart_by_their_uuid() {
+        assert_eq!(device_mapper_kind("LVM-k2vWQdl0xAbCdEfGhIjKlMnOpQrStUvWxYz0123456789abcdefghijkl"), BlockDeviceKind::Lvm);
+        assert_eq!(device_mapper_kind("CRYPT-LUKS2-3f9a1c2b4d5e6f708192a3b4c5d6e7f8-luks-3f9a1c2b"), BlockDeviceKind::Crypt);
+        assert_eq!(device_mapper_kind("mpath-3600508b400105e210000900000490000"), BlockDeviceKind::DeviceMapper);
+    }
+}
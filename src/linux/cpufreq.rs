@@ -0,0 +1,17 @@
+use std::fs;
+use crate::snapshot::CpuFrequencyLimits;
+
+/// Scaling limits and governor of `cpu` (`cpu0`, `cpu1`..). `None` when the core has no cpufreq
+/// driver, which is common in virtual machines.
+pub fn read_limits(cpu: &str) -> Option<CpuFrequencyLimits> {
+    let dir = format!("/sys/devices/system/cpu/{}/cpufreq", cpu);
+    let read = |file: &str| fs::read_to_string(format!("{}/{}", dir, file)).ok().map(|x| x.trim().to_string());
+    // sysfs reports kHz
+    let khz_to_mhz = |value: String| value.parse::<u64>().ok().map(|x| x / 1000);
+
+    Some(CpuFrequencyLimits {
+        min_mhz: read("scaling_min_freq").and_then(khz_to_mhz)?,
+        max_mhz: read("scaling_max_freq").and_then(khz_to_mhz)?,
+        governor: read("scaling_governor").unwrap_or_default(),
+    })
+}
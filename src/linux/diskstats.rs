@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::Path;
+use crate::error::MonitorError;
+use crate::snapshot::{DiskCounters, DiskId};
+
+/// `/proc/diskstats` always counts in 512 byte sectors, whatever the device's real sector size.
+pub const SECTOR_SIZE: u64 = 512;
+
+/// Counters of the block device behind `disk.device`, e.g. `/dev/nvme0n1p2`.
+/// See Documentation/admin-guide/iostats.rst for the fields.
+pub fn disk_counters(disk: &DiskId) -> Result<DiskCounters, MonitorError> {
+    let device = block_device_name(Path::new(&disk.device));
+    let unavailable = || MonitorError::DiskPerformanceUnavailable { disk: device.clone() };
+
+    let content = fs::read_to_string("/proc/diskstats").map_err(|_| unavailable())?;
+    content.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+        .find(|fields| fields.get(2) == Some(&device.as_str()))
+        .and_then(|fields| parse_counters(fields.get(3..)?))
+        .ok_or_else(unavailable)
+}
+
+/// The counters of one device without the major, minor and name columns of diskstats,
+/// which is also the layout of `/sys/class/block/<device>/stat`.
+pub fn parse_counters(fields: &[&str]) -> Option<DiskCounters> {
+    let field = |index: usize| -> Option<u64> { fields.get(index)?.parse().ok() };
+    Some(DiskCounters {
+        bytes_read: field(2)? * SECTOR_SIZE,
+        bytes_written: field(6)? * SECTOR_SIZE,
+        reads: field(0)?,
+        writes: field(4)?,
+        read_time_ms: field(3)?,
+        write_time_ms: field(7)?,
+        busy_time_ms: field(9)?,
+        in_flight: field(8)?,
+        weighted_busy_time_ms: field(10),
+    })
+}
+
+/// `/dev/mapper/root` and `/dev/disk/by-uuid/..` are symlinks, diskstats only knows the kernel name (`dm-0`).
+fn block_device_name(device: &Path) -> String {
+    let resolved = fs::canonicalize(device).unwrap_or_else(|_| device.to_path_buf());
+    match resolved.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => device.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(line: &str) -> Option<DiskCounters> {
+        parse_counters(&line.split_whitespace().skip(3).collect::<Vec<&str>>())
+    }
+
+    #[test]
+    fn a_line_with_discard_and_flush_fields_is_parsed() {
+        let line = " 259       0 nvme0n1 194523 51230 12087626 31457 402219 280712 26419976 512934 0 213456 560882 11230 0 2345678 987 20311 15494";
+
+        assert_eq!(counters(line), Some(DiskCounters {
+            bytes_read: 12087626 * 512,
+            bytes_written: 26419976 * 512,
+            reads: 194523,
+            writes: 402219,
+            read_time_ms: 31457,
+            write_time_ms: 512934,
+            busy_time_ms: 213456,
+            in_flight: 0,
+            weighted_busy_time_ms: Some(560882),
+        }));
+    }
+
+    #[test]
+    fn a_line_of_a_kernel_before_4_18_is_parsed() {
+        let line = "   8       0 sda 2390 1023 98736 1320 812 455 20104 9870 2 6540 11190";
+
+        let parsed = counters(line).unwrap();
+
+        assert_eq!((parsed.bytes_read, parsed.bytes_written), (98736 * SECTOR_SIZE, 20104 * SECTOR_SIZE));
+        assert_eq!((parsed.in_flight, parsed.busy_time_ms, parsed.weighted_busy_time_ms), (2, 6540, Some(11190)));
+    }
+
+    #[test]
+    fn a_short_line_has_no_counters() {
+        assert_eq!(counters("   8       0 sda 2390 1023 98736 1320 812 455 20104 9870 0").map(|x| x.reads), None);
+        assert_eq!(counters("   8       0 sda 2390 1023 98736 1320 812 455 20104 9870 0 6540").and_then(|x| x.weighted_busy_time_ms), None);
+        assert_eq!(counters("   8       0 sda"), None);
+    }
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::Path;
+use crate::snapshot::{SensorId, SensorKind, SensorReading};
+
+const HWMON: &str = "/sys/class/hwmon";
+
+/// Every `fanN_input` of every hwmon chip. sysinfo only reads temperatures, so fans come from here.
+pub fn read_fans() -> Vec<SensorReading> {
+    let chips = match fs::read_dir(HWMON) {
+        Ok(value) => value,
+        Err(_) => return Vec::new(),
+    };
+
+    chips.filter_map(|chip| chip.ok())
+        .flat_map(|chip| read_chip_fans(&chip.path()))
+        .collect()
+}
+
+fn read_chip_fans(chip: &Path) -> Vec<SensorReading> {
+    let read = |file: &str| fs::read_to_string(chip.join(file)).ok().map(|x| x.trim().to_string());
+    let chip_name = read("name").unwrap_or_else(|| chip.file_name().unwrap_or_default().to_string_lossy().into_owned());
+    let files = match fs::read_dir(chip) {
+        Ok(value) => value,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut fans: Vec<SensorReading> = files.filter_map(|file| file.ok())
+        .filter_map(|file| {
+            let file_name = file.file_name().to_string_lossy().into_owned();
+            let fan = file_name.strip_prefix("fan")?.strip_suffix("_input")?.to_string();
+            let rpm: f32 = read(&file_name)?.parse().ok()?;
+            let label = match read(&format!("fan{}_label", fan)) {
+                Some(value) => format!("{} {}", chip_name, value),
+                None => format!("{} fan{}", chip_name, fan),
+            };
+            Some(SensorReading {
+                id: SensorId { kind: SensorKind::Fan, label },
+                current: rpm,
+                max: read(&format!("fan{}_max", fan)).and_then(|x| x.parse().ok()),
+                critical: None,
+            })
+        })
+        .collect();
+    fans.sort_by(|a, b| a.id.label.cmp(&b.id.label));
+    fans
+}
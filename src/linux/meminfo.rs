@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::fs;
+use crate::error::MonitorError;
+use crate::snapshot::MemoryBreakdown;
+
+const PROC_MEMINFO: &str = "/proc/meminfo";
+
+pub fn read() -> Result<MemoryBreakdown, MonitorError> {
+    let content = fs::read_to_string(PROC_MEMINFO)
+        .map_err(|_| MonitorError::ProcFileUnavailable { path: PROC_MEMINFO.to_string() })?;
+    Ok(parse(&content))
+}
+
+/// Values are in kB except the `HugePages_*` page counts. Keys missing on older kernels read as zero.
+fn parse(content: &str) -> MemoryBreakdown {
+    let values: HashMap<&str, u64> = content.lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let value = rest.split_whitespace().next()?.parse().ok()?;
+            Some((key, value))
+        })
+        .collect();
+    let kb = |key: &str| values.get(key).copied().unwrap_or(0) * 1024;
+    let pages = |key: &str| values.get(key).copied().unwrap_or(0);
+
+    MemoryBreakdown {
+        total: kb("MemTotal"),
+        free: kb("MemFree"),
+        available: kb("MemAvailable"),
+        buffers: kb("Buffers"),
+        cached: kb("Cached"),
+        shared: kb("Shmem"),
+        slab_reclaimable: kb("SReclaimable"),
+        slab_unreclaimable: kb("SUnreclaim"),
+        dirty: kb("Dirty"),
+        writeback: kb("Writeback"),
+        huge_pages_total: pages("HugePages_Total") * kb("Hugepagesize"),
+        huge_pages_free: pages("HugePages_Free") * kb("Hugepagesize"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMINFO_EXCERPT: &str = "\
+MemTotal:       16314880 kB
+MemFree:         1203340 kB
+MemAvailable:    9870612 kB
+Buffers:          402196 kB
+Cached:          8120540 kB
+SwapCached:         1024 kB
+Shmem:            612300 kB
+SReclaimable:     550120 kB
+SUnreclaim:       140288 kB
+Dirty:               236 kB
+Writeback:             0 kB
+HugePages_Total:       4
+HugePages_Free:        3
+HugePages_Rsvd:        0
+Hugepagesize:       2048 kB
+";
+
+    #[test]
+    fn kilobyte_values_are_scaled_to_bytes_and_huge_pages_by_their_size() {
+        assert_eq!(parse(MEMINFO_EXCERPT), MemoryBreakdown {
+            total: 16314880 * 1024,
+            free: 1203340 * 1024,
+            available: 9870612 * 1024,
+            buffers: 402196 * 1024,
+            cached: 8120540 * 1024,
+            shared: 612300 * 1024,
+            slab_reclaimable: 550120 * 1024,
+            slab_unreclaimable: 140288 * 1024,
+            dirty: 236 * 1024,
+            writeback: 0,
+            huge_pages_total: 4 * 2048 * 1024,
+            huge_pages_free: 3 * 2048 * 1024,
+        });
+    }
+
+    #[test]
+    fn keys_missing_on_older_kernels_read_as_zero() {
+        let breakdown = parse("MemTotal:  2048 kB\nMemFree:  1024 kB\n");
+
+        assert_eq!(breakdown.total, 2048 * 1024);
+        assert_eq!(breakdown.available, 0);
+        assert_eq!(breakdown.huge_pages_total, 0);
+    }
+}
@@ -0,0 +1,17 @@
+//! Collectors that read the kernel's own accounting under `/proc` and `/sys`.
+//! sysinfo only exposes a summary of most of these, so on linux we parse them ourselves.
+
+pub mod blockdev;
+pub mod cpufreq;
+pub mod diskstats;
+pub mod hwmon;
+pub mod meminfo;
+pub mod net;
+pub mod ping;
+pub mod pressure;
+pub mod process;
+pub mod proc_stat;
+pub mod rapl;
+pub mod sock_diag;
+pub mod statvfs;
+pub mod vmstat;
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::Path;
+use crate::snapshot::{InterfaceAddress, InterfaceDetails, InterfaceKind, Protocol};
+
+/// `ARPHRD_LOOPBACK` of `<linux/if_arp.h>`, the `type` of a loopback interface.
+const ARPHRD_LOOPBACK: u32 = 772;
+
+/// One line of `/proc/net/{tcp,tcp6,udp,udp6}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SocketEntry {
+    pub protocol: Protocol,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    /// Kernel tcp state number, see `tcp_state_name`. udp sockets report 7 (close) or 1 when connected.
+    pub state: u8,
+    /// Bytes waiting to be sent and read, for a listening socket the receive queue is the accept backlog.
+    pub send_queue: u32,
+    pub receive_queue: u32,
+    pub uid: u32,
+    pub inode: u64,
+}
+
+impl SocketEntry {
+    /// Kernel names for tcp, `ss` style for udp which only knows connected or not.
+    pub fn state_name(&self) -> &'static str {
+        match (self.protocol, self.state) {
+            (Protocol::Tcp, state) => tcp_state_name(state),
+            (Protocol::Udp, 1) => "ESTABLISHED",
+            (Protocol::Udp, _) => "UNCONN",
+        }
+    }
+}
+
+/// Every tcp and udp socket of the monitor's network namespace, ipv4 and ipv6.
+pub fn read_sockets() -> Vec<SocketEntry> {
+    [
+        ("/proc/net/tcp", Protocol::Tcp),
+        ("/proc/net/tcp6", Protocol::Tcp),
+        ("/proc/net/udp", Protocol::Udp),
+        ("/proc/net/udp6", Protocol::Udp),
+    ].iter()
+        .filter_map(|(path, protocol)| Some((fs::read_to_string(path).ok()?, *protocol)))
+        .flat_map(|(content, protocol)| {
+            content.lines().skip(1).filter_map(|line| parse_socket_line(line, protocol)).collect::<Vec<SocketEntry>>()
+        })
+        .collect()
+}
+
+/// Socket inode to bound path of `/proc/net/unix`, unnamed sockets map to an empty path.
+pub fn read_unix_sockets() -> HashMap<u64, String> {
+    let content = match fs::read_to_string("/proc/net/unix") {
+        Ok(value) => value,
+        Err(_) => return HashMap::new(),
+    };
+    content.lines().skip(1).filter_map(|line|{
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let inode = fields.get(6)?.parse().ok()?;
+        let path = fields.get(7).map(|x| x.to_string()).unwrap_or_default();
+        Some((inode, path))
+    }).collect()
+}
+
+/// Received and transmitted packets the interface dropped since it came up, from `/sys/class/net/<name>/statistics`.
+pub fn interface_drops(interface_name: &str) -> Option<u64> {
+    let read = |name: &str| -> Option<u64> {
+        fs::read_to_string(format!("/sys/class/net/{}/statistics/{}", interface_name, name)).ok()?.trim().parse().ok()
+    };
+    Some(read("rx_dropped")? + read("tx_dropped")?)
+}
+
+/// Kernel index of the interface, it stays the same across a rename and is never shared.
+pub fn interface_index(interface_name: &str) -> Option<u32> {
+    read_interface_file(interface_name, "ifindex")?.parse().ok()
+}
+
+/// Link state and configuration of `/sys/class/net/<name>`. Values a driver doesn't report
+/// (speed of a wifi or virtual link, carrier of a down interface) are `None`.
+pub fn interface_details(interface_name: &str, addresses: &HashMap<String, Vec<InterfaceAddress>>) -> InterfaceDetails {
+    let read = |name: &str| read_interface_file(interface_name, name);
+    InterfaceDetails {
+        addresses: addresses.get(interface_name).cloned().unwrap_or_default(),
+        operstate: read("operstate"),
+        carrier: read("carrier").map(|x| x == "1"),
+        // -1 while the link is down or when the driver can't tell
+        speed_mbps: read("speed").and_then(|x| x.parse::<i64>().ok()).filter(|x| *x > 0).map(|x| x as u64),
+        duplex: read("duplex").filter(|x| x != "unknown"),
+        mtu: read("mtu").and_then(|x| x.parse().ok()),
+        kind: interface_kind(interface_name),
+    }
+}
+
+/// IPv4 and IPv6 addresses of every interface by name, from getifaddrs(3).
+pub fn interface_addresses() -> HashMap<String, Vec<InterfaceAddress>> {
+    let mut addresses: HashMap<String, Vec<InterfaceAddress>> = HashMap::new();
+    let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+    // SAFETY: on success getifaddrs hands over a list that is released with freeifaddrs below
+    if unsafe { libc::getifaddrs(&mut list) } != 0 {
+        return addresses;
+    }
+    let mut current = list;
+    while !current.is_null() {
+        // SAFETY: every node of the list stays valid until freeifaddrs, addresses are checked for null
+        // and only read as the socket address type their family says
+        let entry = unsafe { &*current };
+        current = entry.ifa_next;
+        if entry.ifa_addr.is_null() {
+            continue;
+        }
+        let name = unsafe { CStr::from_ptr(entry.ifa_name) }.to_string_lossy().into_owned();
+        let address = match unsafe { socket_address(entry.ifa_addr) } {
+            Some(value) => value,
+            None => continue,
+        };
+        let prefix_length = if entry.ifa_netmask.is_null() {
+            0
+        } else {
+            match unsafe { socket_address(entry.ifa_netmask) } {
+                Some(IpAddr::V4(mask)) => u32::from(mask).count_ones() as u8,
+                Some(IpAddr::V6(mask)) => u128::from(mask).count_ones() as u8,
+                None => 0,
+            }
+        };
+        addresses.entry(name).or_default().push(InterfaceAddress { address, prefix_length });
+    }
+    unsafe { libc::freeifaddrs(list) };
+    addresses
+}
+
+/// # Safety
+/// `address` has to point to a valid `sockaddr` as large as its family requires.
+unsafe fn socket_address(address: *const libc::sockaddr) -> Option<IpAddr> {
+    match (*address).sa_family as libc::c_int {
+        libc::AF_INET => {
+            let address = &*(address as *const libc::sockaddr_in);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(address.sin_addr.s_addr))))
+        }
+        libc::AF_INET6 => {
+            let address = &*(address as *const libc::sockaddr_in6);
+            Some(IpAddr::V6(Ipv6Addr::from(address.sin6_addr.s6_addr)))
+        }
+        _ => None,
+    }
+}
+
+/// Loopback by its link type, bridges and tun/tap by their sysfs attributes, a virtual interface whose
+/// link points at another index is the end of a veth pair.
+fn interface_kind(interface_name: &str) -> InterfaceKind {
+    let directory = Path::new("/sys/class/net").join(interface_name);
+    let read = |name: &str| read_interface_file(interface_name, name);
+    if read("type").and_then(|x| x.parse::<u32>().ok()) == Some(ARPHRD_LOOPBACK) {
+        return InterfaceKind::Loopback;
+    }
+    if directory.join("bridge").exists() {
+        return InterfaceKind::Bridge;
+    }
+    if directory.join("tun_flags").exists() {
+        return InterfaceKind::Tun;
+    }
+    let is_virtual = fs::canonicalize(&directory).map(|x| x.starts_with("/sys/devices/virtual")).unwrap_or(false);
+    if !is_virtual {
+        return InterfaceKind::Physical;
+    }
+    // vlans, macvlans and the like name their type, veth doesn't
+    let has_device_type = read("uevent").map(|x| x.lines().any(|line| line.starts_with("DEVTYPE="))).unwrap_or(false);
+    let iflink = read("iflink");
+    if !has_device_type && iflink.is_some() && iflink != read("ifindex") {
+        return InterfaceKind::Veth;
+    }
+    InterfaceKind::Virtual
+}
+
+fn read_interface_file(interface_name: &str, name: &str) -> Option<String> {
+    let content = fs::read_to_string(format!("/sys/class/net/{}/{}", interface_name, name)).ok()?;
+    Some(content.trim().to_string())
+}
+
+pub fn tcp_state_name(state: u8) -> &'static str {
+    match state {
+        1 => "ESTABLISHED",
+        2 => "SYN_SENT",
+        3 => "SYN_RECV",
+        4 => "FIN_WAIT1",
+        5 => "FIN_WAIT2",
+        6 => "TIME_WAIT",
+        7 => "CLOSE",
+        8 => "CLOSE_WAIT",
+        9 => "LAST_ACK",
+        10 => "LISTEN",
+        11 => "CLOSING",
+        12 => "NEW_SYN_RECV",
+        _ => "UNKNOWN",
+    }
+}
+
+/// `   1: 0100007F:BC8F 00000000:0000 0A 00000000:00000000 00:00000000 00000000 65534 0 925 ...`
+fn parse_socket_line(line: &str, protocol: Protocol) -> Option<SocketEntry> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (send_queue, receive_queue) = fields.get(4)?.split_once(':')?;
+    Some(SocketEntry {
+        protocol,
+        local: parse_address(fields.get(1)?)?,
+        remote: parse_address(fields.get(2)?)?,
+        state: u8::from_str_radix(fields.get(3)?, 16).ok()?,
+        send_queue: u32::from_str_radix(send_queue, 16).ok()?,
+        receive_queue: u32::from_str_radix(receive_queue, 16).ok()?,
+        uid: fields.get(7)?.parse().ok()?,
+        inode: fields.get(9)?.parse().ok()?,
+    })
+}
+
+/// The address is printed as 32 bit words read in host byte order, so `0100007F` is 127.0.0.1 on x86.
+/// The port is a plain number.
+fn parse_address(value: &str) -> Option<SocketAddr> {
+    let (address, port) = value.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let words: Option<Vec<u32>> = (0..address.len() / 8)
+        .map(|i| u32::from_str_radix(address.get(i * 8..i * 8 + 8)?, 16).ok())
+        .collect();
+    let bytes: Vec<u8> = words?.iter().flat_map(|word| word.to_ne_bytes()).collect();
+    let ip = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])),
+        16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&bytes);
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(value: &str) -> SocketAddr {
+        value.parse().unwrap()
+    }
+
+    // the addresses in /proc/net are in host byte order, these lines are from x86
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn tcp_lines_give_endpoints_state_and_queues() {
+        let listening = "   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 21934 1 0000000000000000 100 0 0 10 0";
+        let established = "   5: 0A01A8C0:D2F4 2200D9AC:01BB 01 00000010:00000020 02:00000AD1 00000000  1000        0 81234 2 0000000000000000 20 4 30 10 -1";
+
+        assert_eq!(parse_socket_line(listening, Protocol::Tcp), Some(SocketEntry {
+            protocol: Protocol::Tcp,
+            local: address("127.0.0.1:631"),
+            remote: address("0.0.0.0:0"),
+            state: 10,
+            send_queue: 0,
+            receive_queue: 0,
+            uid: 0,
+            inode: 21934,
+        }));
+        let established = parse_socket_line(established, Protocol::Tcp).unwrap();
+        assert_eq!((established.local, established.remote), (address("192.168.1.10:54004"), address("172.217.0.34:443")));
+        assert_eq!((established.state, established.send_queue, established.receive_queue), (1, 16, 32));
+        assert_eq!((established.uid, established.inode), (1000, 81234));
+    }
+
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn tcp6_lines_give_ipv6_and_mapped_ipv4_endpoints() {
+        let line = "   3: 0000000000000000FFFF00000A01A8C0:D2F6 5014002A2B080140000000000E200000:01BB 01 00000000:00000000 02:00000AD1 00000000  1000        0 81240 2 0000000000000000 20 4 30 10 -1";
+        let loopback = "   1: 00000000000000000000000001000000:0277 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 21933 1 0000000000000000 100 0 0 10 0";
+
+        let socket = parse_socket_line(line, Protocol::Tcp).unwrap();
+        assert_eq!(socket.local, address("[::ffff:192.168.1.10]:54006"));
+        assert_eq!(socket.remote, address("[2a00:1450:4001:82b::200e]:443"));
+        assert_eq!(parse_socket_line(loopback, Protocol::Tcp).unwrap().local, address("[::1]:631"));
+    }
+
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn udp_and_udp6_lines_are_parsed_the_same_way() {
+        let udp = "  785: 3500007F:0035 00000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 19522 2 0000000000000000 0";
+        let udp6 = "  623: 00000000000000000000000000000000:14E9 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000   115        0 23456 2 0000000000000000 0";
+
+        let udp = parse_socket_line(udp, Protocol::Udp).unwrap();
+        assert_eq!((udp.protocol, udp.local, udp.state, udp.uid, udp.inode), (Protocol::Udp, address("127.0.0.53:53"), 7, 101, 19522));
+        let udp6 = parse_socket_line(udp6, Protocol::Udp).unwrap();
+        assert_eq!((udp6.local, udp6.remote, udp6.inode), (address("[::]:5353"), address("[::]:0"), 23456));
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let header = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode";
+
+        assert_eq!(parse_socket_line(header, Protocol::Tcp), None);
+        assert_eq!(parse_socket_line("   0: 0100007F:0277 00000000:0000 0A 00000000:00000000", Protocol::Tcp), None);
+        assert_eq!(parse_socket_line("   0: 0100007:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000 0 0 21934", Protocol::Tcp), None);
+        assert_eq!(parse_address("0100007F"), None);
+        assert_eq!(parse_address("0100007F:XYZ"), None);
+    }
+}
@@ -0,0 +1,135 @@
+use std::io;
+use std::mem;
+use std::net::IpAddr;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::{Duration, Instant};
+
+const ICMP_ECHO_REQUEST: u8 = 8;
+const ICMP_ECHO_REPLY: u8 = 0;
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+const ICMPV6_ECHO_REPLY: u8 = 129;
+
+/// Sequence numbers of all echo requests, so a late reply to an earlier request isn't taken for the current one.
+static SEQUENCE: AtomicU16 = AtomicU16::new(0);
+
+/// Round trip of one echo request through an unprivileged ping socket (`SOCK_DGRAM`, `IPPROTO_ICMP`).
+/// The kernel fills in the identifier and only passes our own replies up. Fails with `PermissionDenied`
+/// when the group isn't in `net.ipv4.ping_group_range`.
+pub fn echo(address: IpAddr, timeout: Duration) -> io::Result<Duration> {
+    let (domain, protocol, request_type, reply_type) = match address {
+        IpAddr::V4(_) => (libc::AF_INET, libc::IPPROTO_ICMP, ICMP_ECHO_REQUEST, ICMP_ECHO_REPLY),
+        IpAddr::V6(_) => (libc::AF_INET6, libc::IPPROTO_ICMPV6, ICMPV6_ECHO_REQUEST, ICMPV6_ECHO_REPLY),
+    };
+    // SAFETY: plain socket(2) call, the descriptor is owned right away so it is closed on every path
+    let fd = unsafe { libc::socket(domain, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, protocol) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+    set_receive_timeout(&socket, timeout)?;
+
+    let sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let mut request = [0u8; 16];
+    request[0] = request_type;
+    request[6..8].copy_from_slice(&sequence.to_be_bytes());
+    request[8..].copy_from_slice(b"hwmonitr");
+    if address.is_ipv4() {
+        // the kernel computes the ICMPv6 checksum itself
+        let checksum = internet_checksum(&request);
+        request[2..4].copy_from_slice(&checksum.to_be_bytes());
+    }
+
+    let start = Instant::now();
+    send_to(&socket, &request, address)?;
+    let mut reply = [0u8; 128];
+    loop {
+        // SAFETY: the kernel writes at most reply.len() bytes into the buffer
+        let received = unsafe { libc::recv(socket.as_raw_fd(), reply.as_mut_ptr() as *mut libc::c_void, reply.len(), 0) };
+        if received < 0 {
+            let error = io::Error::last_os_error();
+            return match error.kind() {
+                io::ErrorKind::WouldBlock => Err(io::Error::new(io::ErrorKind::TimedOut, "no echo reply")),
+                _ => Err(error),
+            };
+        }
+        let is_our_reply = received >= 8 && reply[0] == reply_type && reply[6..8] == sequence.to_be_bytes();
+        if is_our_reply {
+            return Ok(start.elapsed());
+        }
+        if start.elapsed() >= timeout {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "no echo reply"));
+        }
+    }
+}
+
+fn set_receive_timeout(socket: &OwnedFd, timeout: Duration) -> io::Result<()> {
+    let value = libc::timeval {
+        tv_sec: timeout.as_secs() as libc::time_t,
+        tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+    };
+    // SAFETY: the option value is a timeval of the given size
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_RCVTIMEO,
+            &value as *const libc::timeval as *const libc::c_void,
+            mem::size_of::<libc::timeval>() as libc::socklen_t,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn send_to(socket: &OwnedFd, packet: &[u8], address: IpAddr) -> io::Result<()> {
+    // SAFETY: the socket addresses are plain data, zeroed and then filled in for the address family,
+    // sendto reads exactly the size passed along
+    let sent = unsafe {
+        match address {
+            IpAddr::V4(ip) => {
+                let mut destination: libc::sockaddr_in = mem::zeroed();
+                destination.sin_family = libc::AF_INET as libc::sa_family_t;
+                destination.sin_addr.s_addr = u32::from(ip).to_be();
+                libc::sendto(
+                    socket.as_raw_fd(),
+                    packet.as_ptr() as *const libc::c_void,
+                    packet.len(),
+                    0,
+                    &destination as *const libc::sockaddr_in as *const libc::sockaddr,
+                    mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                )
+            }
+            IpAddr::V6(ip) => {
+                let mut destination: libc::sockaddr_in6 = mem::zeroed();
+                destination.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                destination.sin6_addr.s6_addr = ip.octets();
+                libc::sendto(
+                    socket.as_raw_fd(),
+                    packet.as_ptr() as *const libc::c_void,
+                    packet.len(),
+                    0,
+                    &destination as *const libc::sockaddr_in6 as *const libc::sockaddr,
+                    mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+                )
+            }
+        }
+    };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// RFC 1071 ones' complement sum.
+fn internet_checksum(packet: &[u8]) -> u16 {
+    let mut sum: u32 = packet.chunks(2)
+        .map(|pair| u32::from(u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])))
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
@@ -0,0 +1,88 @@
+use std::fs;
+use crate::snapshot::{Pressure, PressureLine};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PressureResource {
+    Cpu,
+    Memory,
+    Io,
+}
+
+impl PressureResource {
+    fn path(&self) -> &'static str {
+        match self {
+            PressureResource::Cpu => "/proc/pressure/cpu",
+            PressureResource::Memory => "/proc/pressure/memory",
+            PressureResource::Io => "/proc/pressure/io",
+        }
+    }
+}
+
+/// `None` when the kernel was built without PSI or booted with `psi=0`, both are normal and not an error.
+pub fn read(resource: PressureResource) -> Option<Pressure> {
+    let content = fs::read_to_string(resource.path()).ok()?;
+    parse(&content)
+}
+
+/// `some avg10=0.12 avg60=0.05 avg300=0.01 total=123456`, one line for `some` and one for `full`.
+/// Kernels before 5.13 have no `full` line for cpu.
+fn parse(content: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+    content.lines().for_each(|line|{
+        let mut fields = line.split_whitespace();
+        let target = match fields.next() {
+            Some("some") => &mut some,
+            Some("full") => &mut full,
+            _ => return,
+        };
+        let mut pressure_line = PressureLine::default();
+        fields.filter_map(|field| field.split_once('=')).for_each(|(key, value)|{
+            match key {
+                "avg10" => pressure_line.avg10 = value.parse().unwrap_or(0.0),
+                "avg60" => pressure_line.avg60 = value.parse().unwrap_or(0.0),
+                "avg300" => pressure_line.avg300 = value.parse().unwrap_or(0.0),
+                "total" => pressure_line.total_us = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        });
+        *target = Some(pressure_line);
+    });
+
+    Some(Pressure {
+        some: some?,
+        full,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn some_and_full_lines_are_parsed() {
+        let pressure = parse("\
+some avg10=1.25 avg60=0.50 avg300=0.10 total=5321907
+full avg10=0.75 avg60=0.25 avg300=0.00 total=2047113
+").unwrap();
+
+        assert_eq!(pressure, Pressure {
+            some: PressureLine { avg10: 1.25, avg60: 0.5, avg300: 0.1, total_us: 5321907 },
+            full: Some(PressureLine { avg10: 0.75, avg60: 0.25, avg300: 0.0, total_us: 2047113 }),
+        });
+    }
+
+    #[test]
+    fn cpu_pressure_of_older_kernels_has_no_full_line() {
+        let pressure = parse("some avg10=0.00 avg60=0.03 avg300=0.01 total=88120\n").unwrap();
+
+        assert_eq!(pressure.some.total_us, 88120);
+        assert_eq!(pressure.full, None);
+    }
+
+    #[test]
+    fn a_file_without_a_some_line_is_not_pressure() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("full avg10=0.00 avg60=0.00 avg300=0.00 total=0\n"), None);
+    }
+}
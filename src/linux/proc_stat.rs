@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::fs;
+use crate::error::MonitorError;
+use crate::snapshot::CpuBreakdown;
+
+const PROC_STAT: &str = "/proc/stat";
+
+/// Jiffies one `cpu`/`cpuN` line of `/proc/stat` says the cpu spent in each state since boot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+impl CpuTimes {
+    /// The kernel already counts guest time inside user and nice, so it is not added again.
+    fn total(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+
+    /// Share of the time between `earlier` and `self` spent in each state, in percent.
+    /// A counter that went backwards (cpu went offline and came back) counts as zero.
+    pub fn breakdown_since(&self, earlier: &CpuTimes) -> CpuBreakdown {
+        let total = self.total().saturating_sub(earlier.total());
+        if total == 0 {
+            return CpuBreakdown::default();
+        }
+        let percent = |now: u64, before: u64| (now.saturating_sub(before) as f64 / total as f64 * 100.0) as f32;
+        let guest = percent(self.guest, earlier.guest);
+        let guest_nice = percent(self.guest_nice, earlier.guest_nice);
+
+        CpuBreakdown {
+            user: (percent(self.user, earlier.user) - guest).max(0.0),
+            nice: (percent(self.nice, earlier.nice) - guest_nice).max(0.0),
+            system: percent(self.system, earlier.system),
+            idle: percent(self.idle, earlier.idle),
+            iowait: percent(self.iowait, earlier.iowait),
+            irq: percent(self.irq, earlier.irq),
+            softirq: percent(self.softirq, earlier.softirq),
+            steal: percent(self.steal, earlier.steal),
+            guest: guest + guest_nice,
+        }
+    }
+}
+
+/// Scheduler counters from the non-cpu lines of `/proc/stat`.
+/// Context switches, interrupts and forks count up since boot, the process counts are right now.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SchedulerCounters {
+    pub context_switches: u64,
+    pub interrupts: u64,
+    pub forks: u64,
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ProcStat {
+    /// Keyed by the line label, `cpu` for the total and `cpu0`, `cpu1`.. per core,
+    /// the same names sysinfo gives the cores.
+    pub cpus: HashMap<String, CpuTimes>,
+    pub scheduler: SchedulerCounters,
+}
+
+pub fn read() -> Result<ProcStat, MonitorError> {
+    let content = fs::read_to_string(PROC_STAT)
+        .map_err(|_| MonitorError::ProcFileUnavailable { path: PROC_STAT.to_string() })?;
+    Ok(ProcStat {
+        cpus: parse_cpus(&content),
+        scheduler: parse_scheduler(&content),
+    })
+}
+
+/// `intr` is followed by one column per interrupt line, only the leading total is used.
+fn parse_scheduler(content: &str) -> SchedulerCounters {
+    let mut counters = SchedulerCounters::default();
+    content.lines().for_each(|line|{
+        let mut fields = line.split_whitespace();
+        let key = fields.next();
+        let value = fields.next().and_then(|x| x.parse().ok()).unwrap_or(0);
+        match key {
+            Some("ctxt") => counters.context_switches = value,
+            Some("intr") => counters.interrupts = value,
+            Some("processes") => counters.forks = value,
+            Some("procs_running") => counters.procs_running = value,
+            Some("procs_blocked") => counters.procs_blocked = value,
+            _ => {}
+        }
+    });
+    counters
+}
+
+/// Older kernels print fewer columns, the missing ones stay zero.
+fn parse_cpus(content: &str) -> HashMap<String, CpuTimes> {
+    content.lines()
+        .filter(|line| line.starts_with("cpu"))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+            let values: Vec<u64> = fields.map(|x| x.parse().unwrap_or(0)).collect();
+            let value = |i: usize| values.get(i).copied().unwrap_or(0);
+            Some((name, CpuTimes {
+                user: value(0),
+                nice: value(1),
+                system: value(2),
+                idle: value(3),
+                iowait: value(4),
+                irq: value(5),
+                softirq: value(6),
+                steal: value(7),
+                guest: value(8),
+                guest_nice: value(9),
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROC_STAT_EXCERPT: &str = "\
+cpu  10132153 290696 3084719 46828483 16683 0 25195 0 175628 0
+cpu0 1393280 32966 572056 13343292 6130 0 17875 0 23933 0
+cpu1 1335034 3 1096 13335893 1212 0 7320 0 18 0
+intr 199292 9 20 0 0 0 0 3 0 1 0 0 8 0
+ctxt 300
+btime 1691133546
+processes 4097
+procs_running 2
+procs_blocked 1
+softirq 1130 0 306 3 17 0 0 0 0 0 804
+";
+
+    fn times(user: u64, nice: u64, system: u64, idle: u64, guest: u64, guest_nice: u64) -> CpuTimes {
+        CpuTimes { user, nice, system, idle, guest, guest_nice, ..CpuTimes::default() }
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.001, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn every_cpu_line_is_parsed_by_its_label() {
+        let cpus = parse_cpus(PROC_STAT_EXCERPT);
+
+        assert_eq!(cpus.len(), 3);
+        assert_eq!(cpus["cpu"], CpuTimes {
+            user: 10132153,
+            nice: 290696,
+            system: 3084719,
+            idle: 46828483,
+            iowait: 16683,
+            irq: 0,
+            softirq: 25195,
+            steal: 0,
+            guest: 175628,
+            guest_nice: 0,
+        });
+        assert_eq!(cpus["cpu1"].idle, 13335893);
+        assert_eq!(parse_cpus("cpu0 5 6 7 8\n")["cpu0"], times(5, 6, 7, 8, 0, 0));
+    }
+
+    #[test]
+    fn scheduler_counters_take_the_leading_interrupt_total() {
+        assert_eq!(parse_scheduler(PROC_STAT_EXCERPT), SchedulerCounters {
+            context_switches: 300,
+            interrupts: 199292,
+            forks: 4097,
+            procs_running: 2,
+            procs_blocked: 1,
+        });
+    }
+
+    #[test]
+    fn guest_time_is_taken_out_of_user_and_nice() {
+        let earlier = times(1000, 100, 500, 8000, 200, 20);
+        let now = times(1400, 200, 600, 8400, 300, 70);
+
+        let breakdown = now.breakdown_since(&earlier);
+
+        assert_close(breakdown.user, 30.0);
+        assert_close(breakdown.nice, 5.0);
+        assert_close(breakdown.guest, 15.0);
+        assert_close(breakdown.system, 10.0);
+        assert_close(breakdown.idle, 40.0);
+    }
+
+    #[test]
+    fn counters_that_went_backwards_count_as_zero() {
+        let earlier = times(1000, 100, 500, 8000, 0, 0);
+
+        assert_eq!(times(10, 1, 5, 80, 0, 0).breakdown_since(&earlier), CpuBreakdown::default());
+
+        let breakdown = times(900, 100, 700, 8400, 0, 0).breakdown_since(&earlier);
+        assert_close(breakdown.user, 0.0);
+        assert_close(breakdown.system, 40.0);
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use sysinfo::{Pid, PidExt};
+use crate::linux::net;
+use crate::snapshot::{Protocol, IoPriority, IoPriorityClass, MappingGroup, MemoryCounters, OpenFile, OpenFileKind, OpenFiles};
+
+/// Major faults of `pid` since it started, the 12th field of `/proc/<pid>/stat`.
+/// `None` once the process is gone.
+pub fn major_faults(pid: Pid) -> Option<u64> {
+    let content = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // the name in parentheses may contain spaces, the fields after it are plain
+    let (_, fields) = content.rsplit_once(')')?;
+    fields.split_whitespace().nth(9)?.parse().ok()
+}
+
+/// `/proc/<pid>/io`, cumulative since the process started. Only readable for our own processes without root.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IoCounters {
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub cancelled_write_bytes: u64,
+    pub read_syscalls: u64,
+    pub write_syscalls: u64,
+}
+
+pub fn io_counters(pid: Pid) -> Option<IoCounters> {
+    let content = fs::read_to_string(format!("/proc/{}/io", pid)).ok()?;
+    let values: HashMap<&str, u64> = content.lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            Some((key, value.trim().parse().ok()?))
+        })
+        .collect();
+    Some(IoCounters {
+        read_bytes: *values.get("read_bytes")?,
+        write_bytes: *values.get("write_bytes")?,
+        cancelled_write_bytes: *values.get("cancelled_write_bytes")?,
+        read_syscalls: *values.get("syscr")?,
+        write_syscalls: *values.get("syscw")?,
+    })
+}
+
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+const IOPRIO_CLASS_SHIFT: u32 = 13;
+const IOPRIO_LEVEL_MASK: u32 = (1 << IOPRIO_CLASS_SHIFT) - 1;
+
+/// The I/O priority of `pid` from `ioprio_get(2)`.
+pub fn io_priority(pid: Pid) -> io::Result<IoPriority> {
+    // SAFETY: plain syscall with integer arguments
+    let value = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid.as_u32() as libc::c_int) };
+    if value < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let value = value as u32;
+    let class = match value >> IOPRIO_CLASS_SHIFT {
+        1 => IoPriorityClass::RealTime,
+        2 => IoPriorityClass::BestEffort,
+        3 => IoPriorityClass::Idle,
+        _ => IoPriorityClass::None,
+    };
+    Ok(IoPriority { class, level: (value & IOPRIO_LEVEL_MASK) as u8 })
+}
+
+/// `ioprio_set(2)`, the real time class needs CAP_SYS_ADMIN and other users' processes need root.
+pub fn set_io_priority(pid: Pid, priority: IoPriority) -> io::Result<()> {
+    let class: u32 = match priority.class {
+        IoPriorityClass::None => 0,
+        IoPriorityClass::RealTime => 1,
+        IoPriorityClass::BestEffort => 2,
+        IoPriorityClass::Idle => 3,
+    };
+    let level = if priority.class.has_levels() { u32::from(priority.level.min(7)) } else { 0 };
+    let value = (class << IOPRIO_CLASS_SHIFT | level) as libc::c_int;
+    // SAFETY: plain syscall with integer arguments
+    let result = unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, pid.as_u32() as libc::c_int, value) };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Clock ticks per second of the times in `/proc`, fixed at 100 by the kernel ABI whatever CONFIG_HZ is.
+pub const USER_HZ: f64 = 100.0;
+
+/// One entry of `/proc/<pid>/task`, read from the thread's `stat` and `status`.
+pub struct ThreadStat {
+    pub tid: Pid,
+    pub name: String,
+    pub state: char,
+    /// user + system time in clock ticks.
+    pub cpu_ticks: u64,
+    pub last_cpu: u32,
+    pub voluntary_switches: u64,
+    pub involuntary_switches: u64,
+}
+
+/// Every thread of `pid`. Threads exiting while they're read are left out.
+pub fn threads(pid: Pid) -> Result<Vec<ThreadStat>, String> {
+    let path = format!("/proc/{}/task", pid);
+    let entries = fs::read_dir(&path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    Ok(entries.filter_map(|entry| entry.ok())
+        .filter_map(|entry|{
+            let tid: usize = entry.file_name().to_string_lossy().parse().ok()?;
+            thread_stat(pid, Pid::from(tid))
+        })
+        .collect())
+}
+
+fn thread_stat(pid: Pid, tid: Pid) -> Option<ThreadStat> {
+    let stat = fs::read_to_string(format!("/proc/{}/task/{}/stat", pid, tid)).ok()?;
+    let status = fs::read_to_string(format!("/proc/{}/task/{}/status", pid, tid)).unwrap_or_default();
+    parse_thread_stat(tid, &stat, &status)
+}
+
+/// The name in `stat` is in parentheses and may itself contain spaces and `)`, so the fields are
+/// counted from the last `)`.
+fn parse_thread_stat(tid: Pid, stat: &str, status: &str) -> Option<ThreadStat> {
+    let (head, fields) = stat.rsplit_once(')')?;
+    let name = head.split_once('(')?.1.to_string();
+    // fields after the name, state is the 3rd field of the file so it is the 1st here
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+
+    let switches = |key: &str| status.lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|x| x.trim().parse().ok())
+        .unwrap_or(0);
+
+    Some(ThreadStat {
+        tid,
+        name,
+        state: fields.first()?.chars().next()?,
+        cpu_ticks: utime + stime,
+        last_cpu: fields.get(36)?.parse().ok()?,
+        voluntary_switches: switches("voluntary_ctxt_switches:"),
+        involuntary_switches: switches("nonvoluntary_ctxt_switches:"),
+    })
+}
+
+/// Totals of `/proc/<pid>/smaps_rollup`, which the kernel sums up much faster than we could.
+pub fn smaps_rollup(pid: Pid) -> Result<MemoryCounters, String> {
+    let content = read_proc_file(pid, "smaps_rollup")?;
+    let mut counters = MemoryCounters::default();
+    content.lines().for_each(|line| add_smaps_field(&mut counters, line));
+    Ok(counters)
+}
+
+/// Every mapping of `/proc/<pid>/smaps` (the `maps` lines, each followed by its memory counters),
+/// grouped by what backs it, largest resident group first.
+pub fn mapping_groups(pid: Pid) -> Result<Vec<MappingGroup>, String> {
+    let content = read_proc_file(pid, "smaps")?;
+    Ok(group_mappings(&content))
+}
+
+fn group_mappings(content: &str) -> Vec<MappingGroup> {
+    let mut groups: Vec<MappingGroup> = Vec::new();
+    let mut current: Option<usize> = None;
+
+    content.lines().for_each(|line|{
+        match parse_mapping_header(line) {
+            Some((size, name)) => {
+                let index = match groups.iter().position(|x| x.name == name) {
+                    Some(value) => value,
+                    None => {
+                        groups.push(MappingGroup { name, ..MappingGroup::default() });
+                        groups.len() - 1
+                    }
+                };
+                groups[index].mappings += 1;
+                groups[index].size += size;
+                current = Some(index);
+            }
+            None => {
+                if let Some(index) = current {
+                    add_smaps_field(&mut groups[index].counters, line);
+                }
+            }
+        }
+    });
+
+    groups.sort_by(|a, b| b.counters.rss.cmp(&a.counters.rss));
+    groups
+}
+
+fn read_proc_file(pid: Pid, file: &str) -> Result<String, String> {
+    fs::read_to_string(format!("/proc/{}/{}", pid, file)).map_err(|e| format!("cannot read /proc/{}/{}: {}", pid, file, e))
+}
+
+/// `7f1c2a000000-7f1c2a021000 rw-p 00000000 00:00 0    [heap]`, the path is missing for anonymous memory.
+/// Returns the mapping's size in bytes and its group name.
+fn parse_mapping_header(line: &str) -> Option<(u64, String)> {
+    let mut fields = line.split_whitespace();
+    let (start, end) = fields.next()?.split_once('-')?;
+    let start = u64::from_str_radix(start, 16).ok()?;
+    let end = u64::from_str_radix(end, 16).ok()?;
+    let permissions = fields.next()?;
+    if permissions.len() != 4 {
+        return None;
+    }
+    // offset, device and inode
+    let path: Vec<&str> = fields.skip(3).collect();
+    let name = if path.is_empty() { String::from("[anon]") } else { path.join(" ") };
+    Some((end.saturating_sub(start), name))
+}
+
+fn add_smaps_field(counters: &mut MemoryCounters, line: &str) {
+    let (key, rest) = match line.split_once(':') {
+        Some(value) => value,
+        None => return,
+    };
+    let kb: u64 = match rest.split_whitespace().next().and_then(|x| x.parse().ok()) {
+        Some(value) => value,
+        None => return,
+    };
+    let bytes = kb * 1024;
+    match key {
+        "Rss" => counters.rss += bytes,
+        "Pss" => counters.pss += bytes,
+        "Shared_Clean" => counters.shared_clean += bytes,
+        "Shared_Dirty" => counters.shared_dirty += bytes,
+        "Private_Clean" => counters.private_clean += bytes,
+        "Private_Dirty" => counters.private_dirty += bytes,
+        "Swap" => counters.swap += bytes,
+        _ => {}
+    }
+}
+
+/// Every descriptor in `/proc/<pid>/fd` with its target, sockets resolved to their endpoints.
+pub fn open_files(pid: Pid) -> Result<OpenFiles, String> {
+    let entries = fs::read_dir(format!("/proc/{}/fd", pid)).map_err(|e| format!("cannot read /proc/{}/fd: {}", pid, e))?;
+    let socket_endpoints = socket_endpoints();
+
+    let mut files: Vec<OpenFile> = entries.filter_map(|entry| entry.ok())
+        .filter_map(|entry|{
+            let fd = entry.file_name().to_string_lossy().parse().ok()?;
+            let link = fs::read_link(entry.path()).ok()?.to_string_lossy().into_owned();
+            Some(describe_fd(fd, link, &socket_endpoints))
+        })
+        .collect();
+    files.sort_by_key(|x| x.fd);
+
+    Ok(OpenFiles {
+        entries: files,
+        limit: open_files_limit(pid),
+    })
+}
+
+/// Processes with a descriptor whose target contains `query`, e.g. a path or `socket:[12345]`.
+/// Other users' processes can't be read without root and are skipped.
+pub fn find_open_file(pids: impl Iterator<Item = Pid>, query: &str) -> Vec<(Pid, OpenFile)> {
+    let socket_endpoints = socket_endpoints();
+    pids.flat_map(|pid|{
+        let entries = match fs::read_dir(format!("/proc/{}/fd", pid)) {
+            Ok(value) => value,
+            Err(_) => return Vec::new(),
+        };
+        entries.filter_map(|entry| entry.ok())
+            .filter_map(|entry|{
+                let fd = entry.file_name().to_string_lossy().parse().ok()?;
+                let link = fs::read_link(entry.path()).ok()?.to_string_lossy().into_owned();
+                let file = describe_fd(fd, link, &socket_endpoints);
+                (file.target.contains(query) || file.description.contains(query)).then_some((pid, file))
+            })
+            .collect()
+    }).collect()
+}
+
+/// Socket inode to the process holding it, found through every `/proc/<pid>/fd`.
+/// A socket shared after a fork goes to the lowest pid, sockets of other users' processes are missing without root.
+pub fn socket_owners() -> HashMap<u64, Pid> {
+    let mut pids: Vec<usize> = match fs::read_dir("/proc") {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()?.file_name().to_string_lossy().parse().ok()).collect(),
+        Err(_) => return HashMap::new(),
+    };
+    pids.sort_unstable();
+
+    let mut owners = HashMap::new();
+    pids.into_iter().for_each(|pid|{
+        let entries = match fs::read_dir(format!("/proc/{}/fd", pid)) {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+        entries.filter_map(|entry| fs::read_link(entry.ok()?.path()).ok())
+            .filter_map(|link| link.to_str()?.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok())
+            .for_each(|inode: u64|{
+                owners.entry(inode).or_insert(Pid::from(pid));
+            });
+    });
+    owners
+}
+
+/// Soft `Max open files` of `/proc/<pid>/limits`, `None` when unlimited or unreadable.
+fn open_files_limit(pid: Pid) -> Option<u64> {
+    let content = fs::read_to_string(format!("/proc/{}/limits", pid)).ok()?;
+    let line = content.lines().find(|line| line.starts_with("Max open files"))?;
+    line.trim_start_matches("Max open files").split_whitespace().next()?.parse().ok()
+}
+
+/// Socket inode to a readable endpoint, `tcp 10.0.0.2:51234 -> 1.1.1.1:443 ESTABLISHED` or the unix socket path.
+fn socket_endpoints() -> HashMap<u64, String> {
+    let mut endpoints: HashMap<u64, String> = net::read_sockets().into_iter().map(|socket|{
+        let description = match socket.protocol {
+            Protocol::Tcp => format!("tcp {} -> {} {}", socket.local, socket.remote, net::tcp_state_name(socket.state)),
+            Protocol::Udp => format!("udp {} -> {}", socket.local, socket.remote),
+        };
+        (socket.inode, description)
+    }).collect();
+    net::read_unix_sockets().into_iter().for_each(|(inode, path)|{
+        let description = if path.is_empty() { String::from("unix (unnamed)") } else { format!("unix {}", path) };
+        endpoints.entry(inode).or_insert(description);
+    });
+    endpoints
+}
+
+/// `socket:[123]`, `pipe:[456]`, `anon_inode:[eventfd]` or a path.
+fn describe_fd(fd: u32, link: String, socket_endpoints: &HashMap<u64, String>) -> OpenFile {
+    let inode = |prefix: &str| link.strip_prefix(prefix).and_then(|x| x.trim_matches(|c| c == '[' || c == ']').parse::<u64>().ok());
+    let (kind, description) = if let Some(inode) = inode("socket:") {
+        let description = socket_endpoints.get(&inode).cloned().unwrap_or_else(|| String::from("socket of another namespace or family"));
+        (OpenFileKind::Socket, description)
+    } else if link.starts_with("pipe:") {
+        (OpenFileKind::Pipe, String::new())
+    } else if let Some(name) = link.strip_prefix("anon_inode:") {
+        (OpenFileKind::AnonInode, name.trim_matches(|c| c == '[' || c == ']').to_string())
+    } else if link.starts_with('/') {
+        (OpenFileKind::File, String::new())
+    } else {
+        (OpenFileKind::Other, String::new())
+    };
+    OpenFile { fd, kind, target: link, description }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_thread_name_with_spaces_and_parentheses_is_kept_whole() {
+        let stat = "4242 ((a) b)) S 1 4242 4242 0 -1 4194560 1234 0 0 0 250 75 0 0 20 0 3 0 1000 123456789 2048 \
+18446744073709551615 1 1 0 0 0 0 0 4096 0 0 0 0 17 5 0 0 0 0 0\n";
+        let status = "Name:\t(a) b)\nState:\tS (sleeping)\nvoluntary_ctxt_switches:\t812\nnonvoluntary_ctxt_switches:\t9\n";
+
+        let thread = parse_thread_stat(Pid::from(4242), stat, status).unwrap();
+
+        assert_eq!(thread.name, "(a) b)");
+        assert_eq!(thread.state, 'S');
+        assert_eq!(thread.cpu_ticks, 250 + 75);
+        assert_eq!(thread.last_cpu, 5);
+        assert_eq!((thread.voluntary_switches, thread.involuntary_switches), (812, 9));
+    }
+
+    #[test]
+    fn a_thread_that_exited_before_its_status_was_read_has_no_switches() {
+        let stat = "7 (kworker/0:1) I 2 0 0 0 -1 69238880 0 0 0 0 0 3 0 0 20 0 1 0 20 0 0 \
+18446744073709551615 0 0 0 0 0 0 0 2147483647 0 0 0 0 17 0 0 0 0 0 0\n";
+
+        let thread = parse_thread_stat(Pid::from(7), stat, "").unwrap();
+
+        assert_eq!(thread.name, "kworker/0:1");
+        assert_eq!(thread.cpu_ticks, 3);
+        assert_eq!(thread.voluntary_switches, 0);
+        assert!(parse_thread_stat(Pid::from(7), "7 (kworker/0:1) I 2", "").is_none());
+    }
+
+    const SMAPS_EXCERPT: &str = "\
+55d0c4a00000-55d0c4a21000 r--p 00000000 fd:01 1311017                    /usr/bin/my (a) b) daemon
+Size:                132 kB
+Rss:                 128 kB
+Pss:                  64 kB
+Shared_Clean:        128 kB
+Private_Dirty:         0 kB
+VmFlags: rd mr mw me dw sd
+55d0c4a21000-55d0c4a25000 rw-p 00021000 fd:01 1311017                    /usr/bin/my (a) b) daemon
+Size:                 16 kB
+Rss:                   8 kB
+Pss:                   8 kB
+Private_Dirty:         8 kB
+Swap:                  4 kB
+55d0c5e3a000-55d0c5f5b000 rw-p 00000000 00:00 0                          [heap]
+Size:               1156 kB
+Rss:                1024 kB
+Private_Dirty:      1024 kB
+7f1c2a000000-7f1c2a021000 rw-p 00000000 00:00 0 
+Size:                132 kB
+Rss:                  12 kB
+Private_Dirty:        12 kB
+";
+
+    #[test]
+    fn a_mapping_header_gives_its_size_and_backing() {
+        assert_eq!(parse_mapping_header("7f1c2a000000-7f1c2a021000 rw-p 00000000 00:00 0    [heap]"), Some((0x21000, String::from("[heap]"))));
+        assert_eq!(parse_mapping_header("7f1c2a000000-7f1c2a021000 rw-p 00000000 00:00 0"), Some((0x21000, String::from("[anon]"))));
+        assert_eq!(parse_mapping_header("Rss:                 128 kB"), None);
+        assert_eq!(parse_mapping_header("VmFlags: rd wr mr mw me ac sd"), None);
+    }
+
+    #[test]
+    fn mappings_are_grouped_by_backing_largest_resident_first() {
+        let groups = group_mappings(SMAPS_EXCERPT);
+
+        assert_eq!(groups.iter().map(|x| (x.name.as_str(), x.mappings)).collect::<Vec<_>>(), [
+            ("[heap]", 1),
+            ("/usr/bin/my (a) b) daemon", 2),
+            ("[anon]", 1),
+        ]);
+        assert_eq!(groups[1].size, (132 + 16) * 1024);
+        assert_eq!(groups[1].counters, MemoryCounters {
+            rss: 136 * 1024,
+            pss: 72 * 1024,
+            shared_clean: 128 * 1024,
+            private_dirty: 8 * 1024,
+            swap: 4 * 1024,
+            ..MemoryCounters::default()
+        });
+    }
+}
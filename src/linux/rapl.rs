@@ -0,0 +1,87 @@
+use std::fs;
+
+const POWERCAP: &str = "/sys/class/powercap";
+
+/// Cumulative energy of one RAPL package zone. Power is the difference between two reads.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnergyCounter {
+    /// `package-0`, `package-1`..
+    pub label: String,
+    pub energy_uj: u64,
+    /// The counter wraps back to zero after this.
+    pub max_energy_range_uj: u64,
+}
+
+impl EnergyCounter {
+    /// Average power in watts since `earlier` was read `elapsed_seconds` ago.
+    pub fn watts_since(&self, earlier: &EnergyCounter, elapsed_seconds: f64) -> Option<f32> {
+        if elapsed_seconds <= 0.0 {
+            return None;
+        }
+        let consumed_uj = match self.energy_uj.checked_sub(earlier.energy_uj) {
+            Some(value) => value,
+            None => (self.max_energy_range_uj.checked_sub(earlier.energy_uj)?) + self.energy_uj,
+        };
+        Some((consumed_uj as f64 / 1_000_000.0 / elapsed_seconds) as f32)
+    }
+}
+
+/// Top level `intel-rapl:N` zones (amd cpus use the same name). `energy_uj` is root only on most
+/// current kernels, unreadable zones are left out.
+pub fn read_packages() -> Vec<EnergyCounter> {
+    let zones = match fs::read_dir(POWERCAP) {
+        Ok(value) => value,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut packages: Vec<EnergyCounter> = zones.filter_map(|zone| zone.ok())
+        .filter(|zone| {
+            let name = zone.file_name().to_string_lossy().into_owned();
+            name.starts_with("intel-rapl:") && name.matches(':').count() == 1
+        })
+        .filter_map(|zone| {
+            let path = zone.path();
+            let read = |file: &str| fs::read_to_string(path.join(file)).ok().map(|x| x.trim().to_string());
+            Some(EnergyCounter {
+                label: read("name")?,
+                energy_uj: read("energy_uj")?.parse().ok()?,
+                max_energy_range_uj: read("max_energy_range_uj").and_then(|x| x.parse().ok()).unwrap_or(u64::MAX),
+            })
+        })
+        .collect();
+    packages.sort_by(|a, b| a.label.cmp(&b.label));
+    packages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(energy_uj: u64) -> EnergyCounter {
+        EnergyCounter {
+            label: String::from("package-0"),
+            energy_uj,
+            max_energy_range_uj: 262_143_328_850,
+        }
+    }
+
+    #[test]
+    fn power_is_the_energy_used_per_second() {
+        assert_eq!(package(31_000_000).watts_since(&package(1_000_000), 2.0), Some(15.0));
+    }
+
+    #[test]
+    fn a_counter_that_wrapped_between_two_reads_counts_through_its_range() {
+        let earlier = package(262_143_328_850 - 4_000_000);
+
+        assert_eq!(package(6_000_000).watts_since(&earlier, 2.0), Some(5.0));
+        // a counter above its range is garbage, not a wrap
+        assert_eq!(package(6_000_000).watts_since(&package(262_143_328_851), 2.0), None);
+    }
+
+    #[test]
+    fn without_elapsed_time_there_is_no_power() {
+        assert_eq!(package(31_000_000).watts_since(&package(1_000_000), 0.0), None);
+        assert_eq!(package(1_000_000).watts_since(&package(1_000_000), 0.0), None);
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+/// `<linux/sock_diag.h>` and `<linux/inet_diag.h>`, not all of them are in libc.
+const NETLINK_SOCK_DIAG: libc::c_int = 4;
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+const INET_DIAG_INFO: u16 = 2;
+const NLMSG_HEADER_LENGTH: usize = 16;
+const INET_DIAG_REQ_V2_LENGTH: usize = 56;
+const INET_DIAG_MSG_LENGTH: usize = 72;
+const INET_DIAG_MSG_INODE_OFFSET: usize = 68;
+/// `tcpi_bytes_acked` and `tcpi_bytes_received` of `struct tcp_info`, there since linux 4.1.
+const TCP_INFO_BYTES_ACKED_OFFSET: usize = 120;
+const TCP_INFO_BYTES_RECEIVED_OFFSET: usize = 128;
+const ALL_TCP_STATES: u32 = !0;
+
+/// Bytes a socket moved since it was created.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SocketBytes {
+    pub sent: u64,
+    pub received: u64,
+}
+
+/// Byte counters of every tcp socket (ipv4 and ipv6) of the monitor's network namespace by socket inode.
+/// The kernel keeps no byte counters for udp, so udp traffic can't be attributed this way.
+pub fn tcp_byte_counters() -> io::Result<HashMap<u64, SocketBytes>> {
+    // SAFETY: plain socket(2) call, the descriptor is owned right away so it is closed on every path
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, NETLINK_SOCK_DIAG) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let mut counters = HashMap::new();
+    for family in [libc::AF_INET, libc::AF_INET6] {
+        send_request(&socket, family as u8)?;
+        receive_dump(&socket, &mut counters)?;
+    }
+    Ok(counters)
+}
+
+fn send_request(socket: &OwnedFd, family: u8) -> io::Result<()> {
+    let length = NLMSG_HEADER_LENGTH + INET_DIAG_REQ_V2_LENGTH;
+    let mut request = Vec::with_capacity(length);
+    // struct nlmsghdr
+    request.extend_from_slice(&(length as u32).to_ne_bytes());
+    request.extend_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+    request.extend_from_slice(&((libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16).to_ne_bytes());
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    // struct inet_diag_req_v2, the socket id stays zeroed to dump everything
+    request.push(family);
+    request.push(libc::IPPROTO_TCP as u8);
+    request.push(1 << (INET_DIAG_INFO - 1));
+    request.push(0);
+    request.extend_from_slice(&ALL_TCP_STATES.to_ne_bytes());
+    request.resize(length, 0);
+
+    // SAFETY: sockaddr_nl is plain data, zeroed means the kernel as destination
+    let mut address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    let sent = unsafe {
+        libc::sendto(
+            socket.as_raw_fd(),
+            request.as_ptr() as *const libc::c_void,
+            request.len(),
+            0,
+            &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+            mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Reads datagrams until the kernel closes the dump with `NLMSG_DONE`.
+fn receive_dump(socket: &OwnedFd, counters: &mut HashMap<u64, SocketBytes>) -> io::Result<()> {
+    let mut buffer = vec![0u8; 32 * 1024];
+    loop {
+        // SAFETY: the kernel writes at most buffer.len() bytes into the buffer
+        let received = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+        if received < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut messages = &buffer[..received as usize];
+        while messages.len() >= NLMSG_HEADER_LENGTH {
+            let length = read_u32(messages, 0) as usize;
+            let kind = read_u16(messages, 4);
+            if length < NLMSG_HEADER_LENGTH || length > messages.len() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "truncated netlink message"));
+            }
+            match kind as libc::c_int {
+                libc::NLMSG_DONE => return Ok(()),
+                libc::NLMSG_ERROR if length >= NLMSG_HEADER_LENGTH + 4 => {
+                    let errno = read_u32(messages, NLMSG_HEADER_LENGTH) as i32;
+                    return Err(io::Error::from_raw_os_error(-errno));
+                }
+                _ => {
+                    if let Some((inode, bytes)) = parse_diag_message(&messages[NLMSG_HEADER_LENGTH..length]) {
+                        counters.insert(inode, bytes);
+                    }
+                }
+            }
+            messages = &messages[align(length).min(messages.len())..];
+        }
+    }
+}
+
+/// `struct inet_diag_msg` followed by its attributes, only `INET_DIAG_INFO` is asked for.
+fn parse_diag_message(message: &[u8]) -> Option<(u64, SocketBytes)> {
+    if message.len() < INET_DIAG_MSG_LENGTH {
+        return None;
+    }
+    let inode = read_u32(message, INET_DIAG_MSG_INODE_OFFSET) as u64;
+    let mut attributes = &message[INET_DIAG_MSG_LENGTH..];
+    while attributes.len() >= 4 {
+        // struct rtattr
+        let length = read_u16(attributes, 0) as usize;
+        let kind = read_u16(attributes, 2);
+        if length < 4 || length > attributes.len() {
+            return None;
+        }
+        if kind == INET_DIAG_INFO && length >= 4 + TCP_INFO_BYTES_RECEIVED_OFFSET + 8 {
+            let info = &attributes[4..length];
+            return Some((inode, SocketBytes {
+                // the SYN takes one sequence number and is acknowledged too
+                sent: read_u64(info, TCP_INFO_BYTES_ACKED_OFFSET).saturating_sub(1),
+                received: read_u64(info, TCP_INFO_BYTES_RECEIVED_OFFSET),
+            }));
+        }
+        attributes = &attributes[align(length).min(attributes.len())..];
+    }
+    None
+}
+
+fn align(length: usize) -> usize {
+    (length + 3) & !3
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut value = [0u8; 4];
+    value.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_ne_bytes(value)
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut value = [0u8; 8];
+    value.copy_from_slice(&bytes[offset..offset + 8]);
+    u64::from_ne_bytes(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `struct tcp_info` of a 5.x kernel.
+    const TCP_INFO_LENGTH: usize = 232;
+    const INET_DIAG_MEMINFO: u16 = 1;
+
+    fn diag_message(inode: u32, attributes: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut message = vec![0u8; INET_DIAG_MSG_LENGTH];
+        message[INET_DIAG_MSG_INODE_OFFSET..].copy_from_slice(&inode.to_ne_bytes());
+        attributes.iter().for_each(|(kind, payload)|{
+            message.extend_from_slice(&((4 + payload.len()) as u16).to_ne_bytes());
+            message.extend_from_slice(&kind.to_ne_bytes());
+            message.extend_from_slice(payload);
+            message.resize(align(message.len()), 0);
+        });
+        message
+    }
+
+    fn tcp_info(bytes_acked: u64, bytes_received: u64) -> Vec<u8> {
+        let mut info = vec![0u8; TCP_INFO_LENGTH];
+        info[TCP_INFO_BYTES_ACKED_OFFSET..][..8].copy_from_slice(&bytes_acked.to_ne_bytes());
+        info[TCP_INFO_BYTES_RECEIVED_OFFSET..][..8].copy_from_slice(&bytes_received.to_ne_bytes());
+        info
+    }
+
+    #[test]
+    fn byte_counters_are_read_from_the_info_attribute() {
+        let message = diag_message(48213, &[(INET_DIAG_MEMINFO, vec![0; 16]), (INET_DIAG_INFO, tcp_info(1001, 5000))]);
+
+        assert_eq!(parse_diag_message(&message), Some((48213, SocketBytes { sent: 1000, received: 5000 })));
+    }
+
+    #[test]
+    fn a_socket_that_never_got_its_syn_acknowledged_sent_nothing() {
+        let message = diag_message(7, &[(INET_DIAG_INFO, tcp_info(0, 0))]);
+
+        assert_eq!(parse_diag_message(&message), Some((7, SocketBytes { sent: 0, received: 0 })));
+    }
+
+    #[test]
+    fn a_message_without_the_info_attribute_has_no_counters() {
+        assert_eq!(parse_diag_message(&diag_message(7, &[])), None);
+        assert_eq!(parse_diag_message(&diag_message(7, &[(INET_DIAG_MEMINFO, vec![0; 16])])), None);
+        // before linux 4.1 tcp_info ends ahead of the byte counters
+        assert_eq!(parse_diag_message(&diag_message(7, &[(INET_DIAG_INFO, vec![0; 104])])), None);
+        assert_eq!(parse_diag_message(&[0; INET_DIAG_MSG_LENGTH - 1]), None);
+    }
+
+    #[test]
+    fn a_truncated_attribute_is_rejected() {
+        let message = diag_message(7, &[(INET_DIAG_INFO, tcp_info(1001, 5000))]);
+
+        assert_eq!(parse_diag_message(&message[..INET_DIAG_MSG_LENGTH + 100]), None);
+        assert_eq!(parse_diag_message(&message[..INET_DIAG_MSG_LENGTH + 2]), None);
+    }
+}
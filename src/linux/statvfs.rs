@@ -0,0 +1,27 @@
+use std::ffi::CString;
+use std::io;
+use std::mem;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use crate::snapshot::InodeUsage;
+
+/// Inodes of the filesystem mounted at `mount_point`. `None` for filesystems that allocate
+/// inodes on demand (btrfs, most fuse mounts), they report zero of them.
+pub fn inode_usage(mount_point: &Path) -> io::Result<Option<InodeUsage>> {
+    let path = CString::new(mount_point.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: statvfs is plain data filled in by the call, the path is nul terminated
+    let mut stat: libc::statvfs = unsafe { mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if stat.f_files == 0 {
+        return Ok(None);
+    }
+    // fsfilcnt_t is only 32 bits wide on some targets
+    #[allow(clippy::unnecessary_cast)]
+    Ok(Some(InodeUsage {
+        total: stat.f_files as u64,
+        free: stat.f_ffree as u64,
+    }))
+}
@@ -0,0 +1,69 @@
+use std::fs;
+use crate::error::MonitorError;
+
+const PROC_VMSTAT: &str = "/proc/vmstat";
+
+/// Paging counters since boot. Swap counts are in pages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VmstatCounters {
+    pub swap_in_pages: u64,
+    pub swap_out_pages: u64,
+    /// Minor and major together.
+    pub page_faults: u64,
+    pub major_page_faults: u64,
+}
+
+pub fn read() -> Result<VmstatCounters, MonitorError> {
+    let content = fs::read_to_string(PROC_VMSTAT)
+        .map_err(|_| MonitorError::ProcFileUnavailable { path: PROC_VMSTAT.to_string() })?;
+    Ok(parse(&content))
+}
+
+/// One `name value` pair per line, counters missing on older kernels read as zero.
+fn parse(content: &str) -> VmstatCounters {
+    let mut counters = VmstatCounters::default();
+    content.lines().for_each(|line|{
+        let (key, value) = match line.split_once(' ') {
+            Some((key, value)) => (key, value.trim().parse().unwrap_or(0)),
+            None => return,
+        };
+        match key {
+            "pswpin" => counters.swap_in_pages = value,
+            "pswpout" => counters.swap_out_pages = value,
+            "pgfault" => counters.page_faults = value,
+            "pgmajfault" => counters.major_page_faults = value,
+            _ => {}
+        }
+    });
+    counters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paging_counters_are_picked_out_of_vmstat() {
+        let counters = parse("\
+nr_free_pages 296185
+pgpgin 40967816
+pswpin 1312
+pswpout 5210
+pgfault 920418733
+pgmajfault 48211
+pgrefill 0
+");
+
+        assert_eq!(counters, VmstatCounters {
+            swap_in_pages: 1312,
+            swap_out_pages: 5210,
+            page_faults: 920418733,
+            major_page_faults: 48211,
+        });
+    }
+
+    #[test]
+    fn missing_counters_read_as_zero() {
+        assert_eq!(parse("pgfault 7\n"), VmstatCounters { page_faults: 7, ..VmstatCounters::default() });
+    }
+}
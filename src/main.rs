@@ -1,4 +1,5 @@
 #![warn(clippy::all, rust_2018_idioms)]
+#![allow(non_snake_case)] // the crate keeps its ProcessManager name
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 // When compiling natively:
@@ -12,9 +13,11 @@ fn main() -> eframe::Result<()> {
         native_options,
         Box::new(|cc| {
 
-            let app = ProcessManager::ProcessManagerApp::new(cc);
+            let mut app = ProcessManager::ProcessManagerApp::new(cc);
 
             app.start_updating_system_info();
+            app.start_watching_config();
+            app.start_probing();
             
             Box::new(app)
         }),
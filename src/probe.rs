@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::config::{ProbeConfig, ProbeKind};
+#[cfg(target_os = "linux")]
+use crate::linux::ping;
+use crate::sampler::SamplerCommand;
+
+/// Outcome of one attempt, the error says why it counts as lost.
+#[derive(Clone, Debug)]
+pub struct ProbeResult {
+    pub name: String,
+    pub target: String,
+    pub round_trip: Result<Duration, String>,
+}
+
+/// Runs every configured probe on a thread of its own, so a target that times out never holds up
+/// the sampler or the other probes. Every attempt is handed to the sampler as a `SamplerCommand::ProbeResult`.
+pub struct Prober {
+    probes: Vec<ProbeConfig>,
+    /// The running probes by name and target. Dropping the sender stops the probe's thread.
+    running: HashMap<(String, String), (ProbeConfig, Sender<()>)>,
+}
+
+impl Prober {
+    pub fn new(probes: Vec<ProbeConfig>) -> Self {
+        Self {
+            probes,
+            running: HashMap::new(),
+        }
+    }
+
+    /// Runs until the config watcher hangs up, each probe stops on its own once the sampler does.
+    pub fn spawn(mut self, configs: Receiver<Vec<ProbeConfig>>, results: Sender<SamplerCommand>) {
+        thread::spawn(move || {
+            let probes = std::mem::take(&mut self.probes);
+            self.set_probes(probes, &results);
+            while let Ok(probes) = configs.recv() {
+                self.set_probes(probes, &results);
+            }
+        });
+    }
+
+    /// Stops the probes that are gone or changed and starts the new ones, the others keep their schedule.
+    fn set_probes(&mut self, probes: Vec<ProbeConfig>, results: &Sender<SamplerCommand>) {
+        self.running.retain(|_, (probe, _)| probes.contains(probe));
+        for probe in probes {
+            let key = (probe.name().to_string(), probe.target.clone());
+            self.running.entry(key).or_insert_with(|| {
+                let (stop, stopped) = mpsc::channel();
+                let results = results.clone();
+                let config = probe.clone();
+                thread::spawn(move || run(&config, &stopped, &results));
+                (probe, stop)
+            });
+        }
+    }
+}
+
+/// Measures the probe once per interval until `stop` hangs up. An attempt that finishes after that
+/// is thrown away, the probe is no longer in the config.
+fn run(probe: &ProbeConfig, stop: &Receiver<()>, results: &Sender<SamplerCommand>) {
+    loop {
+        let started = Instant::now();
+        let round_trip = measure(probe);
+        if stop.try_recv() == Err(TryRecvError::Disconnected) {
+            return;
+        }
+
+        let result = ProbeResult {
+            name: probe.name().to_string(),
+            target: probe.target.clone(),
+            round_trip,
+        };
+        if results.send(SamplerCommand::ProbeResult(result)).is_err() {
+            return;
+        }
+
+        if stop.recv_timeout(probe.interval().saturating_sub(started.elapsed())) != Err(RecvTimeoutError::Timeout) {
+            return;
+        }
+    }
+}
+
+/// Round trip of one attempt. Name resolution happens before the clock starts.
+pub fn measure(probe: &ProbeConfig) -> Result<Duration, String> {
+    match probe.kind {
+        ProbeKind::Tcp => {
+            let address = resolve(&probe.target)?;
+            let start = Instant::now();
+            TcpStream::connect_timeout(&address, probe.timeout()).map_err(|e| e.to_string())?;
+            Ok(start.elapsed())
+        }
+        ProbeKind::Icmp => {
+            let address = resolve(&format!("{}:0", probe.target)).map(|x| x.ip())?;
+            icmp_echo(address, probe.timeout())
+        }
+    }
+}
+
+fn resolve(target: &str) -> Result<SocketAddr, String> {
+    target.to_socket_addrs()
+        .map_err(|e| format!("cannot resolve {}: {}", target, e))?
+        .next()
+        .ok_or_else(|| format!("{} has no address", target))
+}
+
+#[cfg(target_os = "linux")]
+fn icmp_echo(address: IpAddr, timeout: Duration) -> Result<Duration, String> {
+    ping::echo(address, timeout).map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => String::from("ping sockets are not allowed, see net.ipv4.ping_group_range"),
+        _ => e.to_string(),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn icmp_echo(_address: IpAddr, _timeout: Duration) -> Result<Duration, String> {
+    Err(String::from("icmp probes are only supported on linux"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use super::*;
+
+    fn tcp_probe(target: String) -> ProbeConfig {
+        ProbeConfig {
+            name: None,
+            kind: ProbeKind::Tcp,
+            target,
+            interval_ms: 1000,
+            timeout_ms: 500,
+        }
+    }
+
+    #[test]
+    fn tcp_probe_reaches_a_localhost_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let probe = tcp_probe(listener.local_addr().unwrap().to_string());
+
+        let round_trip = measure(&probe).unwrap();
+
+        assert!(round_trip < probe.timeout());
+    }
+
+    #[test]
+    fn tcp_probe_to_a_closed_port_is_lost() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        assert!(measure(&tcp_probe(address.to_string())).is_err());
+    }
+
+    #[test]
+    fn a_probe_repeats_on_its_own_interval_until_stopped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let probe = ProbeConfig { interval_ms: 50, ..tcp_probe(listener.local_addr().unwrap().to_string()) };
+        let (stop, stopped) = mpsc::channel();
+        let (results, received) = mpsc::channel();
+        let worker = thread::spawn({
+            let probe = probe.clone();
+            move || run(&probe, &stopped, &results)
+        });
+
+        let first_at = Instant::now();
+        for _ in 0..2 {
+            match received.recv_timeout(Duration::from_secs(5)).unwrap() {
+                SamplerCommand::ProbeResult(result) => {
+                    assert_eq!((result.name.as_str(), result.target.as_str()), (probe.name(), probe.target.as_str()));
+                    assert!(result.round_trip.is_ok());
+                }
+                _ => panic!("expected a probe result"),
+            }
+        }
+        assert!(first_at.elapsed() >= Duration::from_millis(40));
+
+        drop(stop);
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn an_attempt_of_a_removed_probe_is_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let probe = tcp_probe(listener.local_addr().unwrap().to_string());
+        let (stop, stopped) = mpsc::channel::<()>();
+        let (results, received) = mpsc::channel();
+        drop(stop);
+
+        run(&probe, &stopped, &results);
+
+        assert!(received.try_recv().is_err());
+    }
+
+    #[test]
+    fn removed_probes_are_stopped_and_changed_ones_restarted() {
+        let (results, _received) = mpsc::channel();
+        let kept = tcp_probe(String::from("127.0.0.1:1"));
+        let changed = tcp_probe(String::from("127.0.0.1:2"));
+        let removed = tcp_probe(String::from("127.0.0.1:3"));
+        let mut prober = Prober::new(Vec::new());
+        prober.set_probes(vec![kept.clone(), changed.clone(), removed], &results);
+
+        let changed = ProbeConfig { interval_ms: 2000, ..changed };
+        prober.set_probes(vec![kept.clone(), changed.clone()], &results);
+
+        assert_eq!(prober.running.len(), 2);
+        assert_eq!(prober.running[&(kept.name().to_string(), kept.target.clone())].0, kept);
+        assert_eq!(prober.running[&(changed.name().to_string(), changed.target.clone())].0, changed);
+    }
+}
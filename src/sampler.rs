@@ -0,0 +1,1477 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Instant, SystemTime};
+use itertools::Itertools;
+use rand::prelude::*;
+use sysinfo::{MacAddr, NetworkExt, NetworksExt, NetworkData, System, SystemExt, ComponentExt, CpuExt, Disk, DiskExt, DiskKind, Pid, ProcessExt};
+use crate::config::Config;
+use crate::error::{ErrorLog, MonitorError};
+use crate::hotplug;
+#[cfg(target_os = "linux")]
+use crate::linux::diskstats::disk_counters;
+#[cfg(target_os = "linux")]
+use crate::linux::{blockdev, cpufreq, hwmon, meminfo, net, process, statvfs, vmstat};
+#[cfg(target_os = "linux")]
+use crate::linux::blockdev::TopologyStamp;
+#[cfg(target_os = "linux")]
+use crate::linux::vmstat::VmstatCounters;
+#[cfg(target_os = "linux")]
+use crate::linux::pressure::{self, PressureResource};
+#[cfg(target_os = "linux")]
+use crate::linux::rapl::{self, EnergyCounter};
+#[cfg(target_os = "linux")]
+use crate::linux::sock_diag::{self, SocketBytes};
+#[cfg(target_os = "linux")]
+use crate::linux::proc_stat::{self, CpuTimes, SchedulerCounters};
+#[cfg(target_os = "linux")]
+use crate::linux::process::IoCounters;
+#[cfg(windows)]
+use crate::windows::disk_counters;
+use crate::probe::ProbeResult;
+use crate::schedule::{OpenViews, RefreshSchedule, Subsystem};
+#[cfg(target_os = "linux")]
+use crate::snapshot::{BlockDevice, ConnectionInformations, Pressure, PressureLine, ProcessIo};
+use crate::snapshot::{Snapshot, CpuFrequencyLimits, ProbeInformations, ProcessDetails, ThreadInformations, ProcessMemory, OpenFiles, OpenFileMatch, OpenFileSearch, SchedulerInformations, PagingInformations, PressureInformations, SensorId, SensorInformations, SensorKind, SensorReading, Data, CpuData, DiskId, DiskCounters, DiskInformations, DiskIoStats, DiskMeasurement, InodeUsage, IoPriority, SpaceSample, SpaceUsage, NetworkInformations, NetworkDisplay, NetworkTotals, InterfaceId, InterfaceAddress, InterfaceDetails, ProcessInformations, SecificProcess};
+
+/// Requests from the UI and the config watcher, handled between refreshes.
+pub enum SamplerCommand {
+    KillProcess(Pid),
+    ApplyConfig(Result<Box<Config>, String>),
+    ClearErrors,
+    /// Start collecting `ProcessDetails` for the process, `None` stops it.
+    InspectProcess(Option<Pid>),
+    /// One attempt of the probe thread.
+    ProbeResult(ProbeResult),
+    /// Look for processes with a descriptor pointing at something containing the text.
+    FindOpenFile(String),
+    SetIoPriority(Pid, IoPriority),
+    /// Which views that need their own sampling are open.
+    SetOpenViews(OpenViews),
+}
+
+/// Owns the `System` and every collected history. Runs on its own thread and publishes a fresh
+/// `Snapshot` after each refresh, so a slow refresh never holds up a frame.
+pub struct Sampler {
+    system: System,
+    snapshot: Snapshot,
+    refresh_schedule: RefreshSchedule,
+    open_views: OpenViews,
+    last_network_refresh: Option<Instant>,
+    last_process_refresh: Option<Instant>,
+    /// When the cpufreq limits of every core were read last, they are read far less often than the cpus.
+    last_frequency_limits_refresh: Option<Instant>,
+    #[cfg(target_os = "linux")]
+    last_cpu_times: HashMap<String, CpuTimes>,
+    #[cfg(target_os = "linux")]
+    last_scheduler_counters: Option<(SchedulerCounters, Instant)>,
+    #[cfg(target_os = "linux")]
+    last_energy_counters: Option<(Vec<EnergyCounter>, Instant)>,
+    #[cfg(target_os = "linux")]
+    last_pressure: HashMap<PressureResource, (Pressure, Instant)>,
+    #[cfg(target_os = "linux")]
+    last_vmstat: Option<(VmstatCounters, Instant)>,
+    #[cfg(target_os = "linux")]
+    last_major_faults: Option<(HashMap<Pid, u64>, Instant)>,
+    /// `/proc/<pid>/io` of every readable process at the previous process refresh.
+    #[cfg(target_os = "linux")]
+    last_process_io: Option<(HashMap<Pid, IoCounters>, Instant)>,
+    /// cpu ticks of each thread of the inspected process.
+    #[cfg(target_os = "linux")]
+    last_thread_ticks: Option<(HashMap<Pid, u64>, Instant)>,
+    inspected_process: Option<Pid>,
+    /// Resident memory of each mapping group when the inspected process was opened.
+    mapping_baseline: HashMap<String, u64>,
+    inspected_network_history: Data<f32>,
+    /// tcp bytes per second by pid, empty while nothing shows them.
+    process_network_rates: HashMap<Pid, f32>,
+    /// Bytes of every tcp socket at the previous process refresh.
+    #[cfg(target_os = "linux")]
+    last_socket_bytes: Option<(HashMap<u64, SocketBytes>, Instant)>,
+    /// Socket inode to the process holding it, shared by the NET column and the connections panel.
+    #[cfg(target_os = "linux")]
+    socket_owners: HashMap<u64, Pid>,
+    /// Whether `socket_owners` was already rebuilt during the current refresh.
+    #[cfg(target_os = "linux")]
+    socket_owners_rebuilt: bool,
+    /// Counters of every node of the block device tree at the previous disk refresh, by kernel name.
+    #[cfg(target_os = "linux")]
+    last_block_counters: HashMap<String, DiskMeasurement>,
+    /// What `snapshot.block_devices` was built from, `None` when it has to be built again.
+    #[cfg(target_os = "linux")]
+    block_topology_stamp: Option<TopologyStamp>,
+}
+
+impl Sampler {
+    pub fn new(sys: System, config: Config, config_error: Option<String>) -> Self {
+        let history_length = config.sampling.history_length;
+        let mut error_log = ErrorLog::default();
+
+        let disks_informations = sys.disks().iter()
+            .map(|x| disk_informations(x, &config, &mut error_log))
+            .collect();
+
+        let network_informations = sys.networks().iter()
+            .map(|(name, _)| (interface_id(name), name))
+            .sorted_by(|a, b| a.0.cmp(&b.0))
+            .enumerate()
+            .map(|(i, (id, name))| network_informations(i + 1, id, name, history_length))
+            .collect();
+
+        let snapshot = Snapshot {
+            total_cpu_usage: 0,
+            memory_usage: 0,
+            swap_usage: 0,
+            total_swap: 0,
+            cpu_performance_data_points: Data::new(history_length),
+            cpu_breakdown_data_points: Data::new(history_length),
+            memory_usage_data_points: Data::new(history_length),
+            memory_breakdown_data_points: Data::new(history_length),
+            paging_informations: PagingInformations::new(history_length),
+            swap_usage_data_points: Data::new(history_length),
+            cpus_performance_data_points: CpuData::new(sys.cpus(), 1, history_length),
+            network_informations,
+            disks_informations,
+            process_informations: HashMap::new(),
+            process_details: None,
+            open_file_search: None,
+            connections: Ok(Vec::new()),
+            process_io: Ok(Vec::new()),
+            block_devices: Ok(Vec::new()),
+            probes: config.probes.iter().map(|x| ProbeInformations::new(x, history_length)).collect(),
+            scheduler_informations: SchedulerInformations::new(history_length),
+            sensors_informations: Vec::new(),
+            cpu_pressure: PressureInformations::new(history_length),
+            memory_pressure: PressureInformations::new(history_length),
+            io_pressure: PressureInformations::new(history_length),
+            config,
+            config_error,
+            error_log,
+        };
+
+        Self {
+            system: sys,
+            snapshot,
+            refresh_schedule: RefreshSchedule::new(),
+            open_views: OpenViews::default(),
+            last_network_refresh: None,
+            last_process_refresh: None,
+            last_frequency_limits_refresh: None,
+            #[cfg(target_os = "linux")]
+            last_cpu_times: HashMap::new(),
+            #[cfg(target_os = "linux")]
+            last_scheduler_counters: None,
+            #[cfg(target_os = "linux")]
+            last_energy_counters: None,
+            #[cfg(target_os = "linux")]
+            last_pressure: HashMap::new(),
+            #[cfg(target_os = "linux")]
+            last_vmstat: None,
+            #[cfg(target_os = "linux")]
+            last_major_faults: None,
+            #[cfg(target_os = "linux")]
+            last_process_io: None,
+            #[cfg(target_os = "linux")]
+            last_thread_ticks: None,
+            inspected_process: None,
+            mapping_baseline: HashMap::new(),
+            inspected_network_history: Data::new(history_length),
+            process_network_rates: HashMap::new(),
+            #[cfg(target_os = "linux")]
+            last_socket_bytes: None,
+            #[cfg(target_os = "linux")]
+            socket_owners: HashMap::new(),
+            #[cfg(target_os = "linux")]
+            socket_owners_rebuilt: false,
+            #[cfg(target_os = "linux")]
+            last_block_counters: HashMap::new(),
+            #[cfg(target_os = "linux")]
+            block_topology_stamp: None,
+        }
+    }
+
+    pub fn snapshot(&self) -> Arc<Snapshot> {
+        Arc::new(self.snapshot.clone())
+    }
+
+    /// Runs until the UI drops its end of either channel.
+    pub fn spawn(mut self, snapshots: Sender<Arc<Snapshot>>, commands: Receiver<SamplerCommand>) {
+        thread::spawn(move || {
+            loop {
+                let due_subsystems = self.refresh_schedule.take_due(Instant::now(), &self.snapshot.config, &self.open_views);
+                #[cfg(target_os = "linux")]
+                {
+                    self.socket_owners_rebuilt = false;
+                }
+
+                for subsystem in &due_subsystems {
+                    match subsystem {
+                        Subsystem::Cpu => self.refresh_cpu(),
+                        Subsystem::Memory => self.refresh_memory(),
+                        Subsystem::Processes => self.refresh_processes(),
+                        Subsystem::ProcessIo => self.refresh_process_io(),
+                        Subsystem::ProcessNetwork => self.refresh_process_network(),
+                        Subsystem::DiskIo => self.refresh_disk_io(),
+                        Subsystem::BlockDevices => self.refresh_block_devices(),
+                        Subsystem::DiskSpace => self.refresh_disk_space(),
+                        Subsystem::Network => self.refresh_network(),
+                        Subsystem::Sensors => self.refresh_sensors(),
+                        Subsystem::Connections => self.refresh_connections(),
+                    }
+                }
+
+                if !due_subsystems.is_empty() && snapshots.send(self.snapshot()).is_err() {
+                    return;
+                }
+
+                let sleep_time = self.refresh_schedule.time_until_next(Instant::now(), &self.snapshot.config, &self.open_views);
+                match commands.recv_timeout(sleep_time) {
+                    Ok(command) => {
+                        self.handle_command(command);
+                        while let Ok(command) = commands.try_recv() {
+                            self.handle_command(command);
+                        }
+                        if snapshots.send(self.snapshot()).is_err() {
+                            return;
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+    }
+
+    fn handle_command(&mut self, command: SamplerCommand) {
+        match command {
+            SamplerCommand::KillProcess(pid) => {
+                match self.system.process(pid) {
+                    Some(process) => {
+                        process.kill();
+                    }
+                    None => {
+                        self.snapshot.error_log.push(MonitorError::ProcessNotFound { pid });
+                    }
+                }
+            }
+            SamplerCommand::ApplyConfig(Ok(config)) => {
+                self.apply_config(*config);
+                self.snapshot.config_error = None;
+            }
+            SamplerCommand::ApplyConfig(Err(e)) => {
+                self.snapshot.config_error = Some(e);
+            }
+            SamplerCommand::ClearErrors => {
+                self.snapshot.error_log.clear();
+            }
+            SamplerCommand::InspectProcess(pid) => {
+                if self.inspected_process != pid {
+                    self.mapping_baseline.clear();
+                    self.inspected_network_history = Data::new(self.snapshot.config.sampling.history_length);
+                    #[cfg(target_os = "linux")]
+                    {
+                        self.last_thread_ticks = None;
+                    }
+                }
+                self.inspected_process = pid;
+                self.refresh_process_details();
+            }
+            SamplerCommand::ProbeResult(result) => {
+                if let Some(probe) = self.snapshot.probes.iter_mut().find(|x| x.name == result.name && x.target == result.target) {
+                    probe.sent += 1;
+                    match result.round_trip {
+                        Ok(round_trip) => {
+                            probe.round_trips.push(Some(round_trip.as_secs_f32() * 1000.0));
+                            probe.last_error = None;
+                        }
+                        Err(e) => {
+                            probe.round_trips.push(None);
+                            probe.lost += 1;
+                            probe.last_error = Some(e);
+                        }
+                    }
+                }
+            }
+            SamplerCommand::FindOpenFile(query) => {
+                let matches = self.find_open_file(&query);
+                self.snapshot.open_file_search = Some(OpenFileSearch { query, matches });
+            }
+            SamplerCommand::SetOpenViews(views) => self.set_open_views(views),
+            SamplerCommand::SetIoPriority(pid, priority) => {
+                if let Err(reason) = set_io_priority(pid, priority) {
+                    self.snapshot.error_log.push(MonitorError::IoPriorityNotSet { pid, reason });
+                }
+            }
+        }
+    }
+
+    /// A closed view's data is dropped, so reopening it never compares against a sample from long ago.
+    fn set_open_views(&mut self, views: OpenViews) {
+        if self.open_views.process_io && !views.process_io {
+            self.snapshot.process_io = Ok(Vec::new());
+            #[cfg(target_os = "linux")]
+            {
+                self.last_process_io = None;
+            }
+        }
+        if self.open_views.process_network && !views.process_network {
+            self.process_network_rates.clear();
+            #[cfg(target_os = "linux")]
+            {
+                self.last_socket_bytes = None;
+            }
+        }
+        if self.open_views.block_devices && !views.block_devices {
+            self.snapshot.block_devices = Ok(Vec::new());
+            #[cfg(target_os = "linux")]
+            {
+                self.last_block_counters.clear();
+                self.block_topology_stamp = None;
+            }
+        }
+        self.open_views = views;
+    }
+
+    fn apply_config(&mut self, config: Config) {
+        let history_length = config.sampling.history_length;
+        self.last_frequency_limits_refresh = None;
+        self.inspected_network_history.set_data_points(history_length);
+        let snapshot = &mut self.snapshot;
+        snapshot.cpu_performance_data_points.set_data_points(history_length);
+        snapshot.cpu_breakdown_data_points.set_data_points(history_length);
+        snapshot.memory_usage_data_points.set_data_points(history_length);
+        snapshot.memory_breakdown_data_points.set_data_points(history_length);
+        snapshot.paging_informations.set_data_points(history_length);
+        snapshot.swap_usage_data_points.set_data_points(history_length);
+        snapshot.cpus_performance_data_points.iter_mut().for_each(|cpu|{
+            cpu.plot_points.set_data_points(history_length);
+            cpu.sample_times.set_data_points(history_length);
+            cpu.frequency_plot_points.set_data_points(history_length);
+        });
+        snapshot.scheduler_informations.set_data_points(history_length);
+        snapshot.cpu_pressure.set_data_points(history_length);
+        snapshot.memory_pressure.set_data_points(history_length);
+        snapshot.io_pressure.set_data_points(history_length);
+        snapshot.sensors_informations.iter_mut().for_each(|sensor|{
+            sensor.plot_points.set_data_points(history_length);
+        });
+        snapshot.disks_informations.iter_mut().for_each(|disk|{
+            disk.io_data_points.set_data_points(history_length);
+            disk.space_history.set_data_points(history_length);
+        });
+        snapshot.network_informations.iter_mut().for_each(|net|{
+            net.network_display.set_data_points(history_length);
+        });
+        // a probe keeps its history as long as it measures the same thing
+        let mut previous_probes = std::mem::take(&mut snapshot.probes);
+        snapshot.probes = config.probes.iter().map(|probe|{
+            match previous_probes.iter().position(|x| x.name == probe.name() && x.kind == probe.kind && x.target == probe.target) {
+                Some(index) => {
+                    let mut probe = previous_probes.swap_remove(index);
+                    probe.round_trips.set_data_points(history_length);
+                    probe
+                }
+                None => ProbeInformations::new(probe, history_length),
+            }
+        }).collect();
+        snapshot.config = config;
+    }
+
+    fn refresh_cpu(&mut self) {
+        let history_length = self.snapshot.config.sampling.history_length;
+        let retention = self.snapshot.config.removed_device_retention();
+        // the limits only change when someone writes to sysfs, so they go with the slow disk space cadence
+        let now = Instant::now();
+        let limits_due = self.last_frequency_limits_refresh
+            .map_or(true, |x| now.duration_since(x) >= self.snapshot.config.refresh_interval(Subsystem::DiskSpace));
+        if limits_due {
+            self.last_frequency_limits_refresh = Some(now);
+        }
+        let sys = &mut self.system;
+        sys.refresh_cpu();
+
+        let processor = sys.global_cpu_info().cpu_usage();
+
+        let cpus = &mut self.snapshot.cpus_performance_data_points;
+        let mut auto_color_index = 1 + 2 * cpus.len();
+        hotplug::reconcile(cpus, sys.cpus(), |x| x.name().to_string(), |x|{
+            let mut cpu = CpuData::from_cpu(x, auto_color_index, history_length);
+            cpu.frequency_limits = cpu_frequency_limits(&cpu.name);
+            auto_color_index += 2;
+            cpu
+        }, now, retention);
+
+        let sample_time = SystemTime::now();
+        sys.cpus().iter().for_each(|x|{
+            if let Some(cpu) = cpus.iter_mut().find(|y| y.name == x.name()) {
+                cpu.usage = x.cpu_usage();
+                cpu.plot_points.push(cpu.usage);
+                cpu.sample_times.push(sample_time);
+                cpu.frequency = x.frequency();
+                cpu.frequency_plot_points.push(cpu.frequency as f32);
+                if limits_due {
+                    cpu.frequency_limits = cpu_frequency_limits(&cpu.name);
+                }
+            }
+        });
+
+        self.snapshot.total_cpu_usage = processor.round() as u64;
+        self.snapshot.cpu_performance_data_points.push(processor);
+
+        let load_average = sys.load_average();
+        let scheduler = &mut self.snapshot.scheduler_informations;
+        scheduler.load_average_one.push(load_average.one as f32);
+        scheduler.load_average_five.push(load_average.five as f32);
+        scheduler.load_average_fifteen.push(load_average.fifteen as f32);
+
+        #[cfg(target_os = "linux")]
+        self.refresh_proc_stat();
+        #[cfg(target_os = "linux")]
+        self.refresh_pressure(PressureResource::Cpu);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn refresh_pressure(&mut self, resource: PressureResource) {
+        let informations = match resource {
+            PressureResource::Cpu => &mut self.snapshot.cpu_pressure,
+            PressureResource::Memory => &mut self.snapshot.memory_pressure,
+            PressureResource::Io => &mut self.snapshot.io_pressure,
+        };
+        let now = Instant::now();
+        let current = pressure::read(resource);
+        informations.latest = current;
+        let current = match current {
+            Some(value) => value,
+            None => return,
+        };
+
+        if let Some((last, last_time)) = self.last_pressure.get(&resource) {
+            let elapsed_us = now.duration_since(*last_time).as_micros() as f64;
+            if elapsed_us > 0.0 {
+                let stalled = |now: &PressureLine, before: &PressureLine| (now.total_us.saturating_sub(before.total_us) as f64 / elapsed_us * 100.0).min(100.0) as f32;
+                informations.some_plot_points.push(stalled(&current.some, &last.some));
+                if let (Some(full), Some(last_full)) = (&current.full, &last.full) {
+                    informations.full_plot_points.push(stalled(full, last_full));
+                }
+            }
+        }
+        self.last_pressure.insert(resource, (current, now));
+    }
+
+    #[cfg(target_os = "linux")]
+    fn refresh_proc_stat(&mut self) {
+        let proc_stat = match proc_stat::read() {
+            Ok(value) => value,
+            Err(e) => {
+                self.snapshot.error_log.push(e);
+                return;
+            }
+        };
+
+        let now = Instant::now();
+        let counters = proc_stat.scheduler;
+        let scheduler = &mut self.snapshot.scheduler_informations;
+        if let Some((last, last_time)) = self.last_scheduler_counters {
+            let elapsed_time = now.duration_since(last_time).as_secs_f64();
+            if elapsed_time > 0.0 {
+                let per_second = |now: u64, before: u64| (now.saturating_sub(before) as f64 / elapsed_time) as f32;
+                scheduler.context_switches_per_second.push(per_second(counters.context_switches, last.context_switches));
+                scheduler.interrupts_per_second.push(per_second(counters.interrupts, last.interrupts));
+                scheduler.forks_per_second.push(per_second(counters.forks, last.forks));
+            }
+        }
+        scheduler.procs_running.push(counters.procs_running as f32);
+        scheduler.procs_blocked.push(counters.procs_blocked as f32);
+        self.last_scheduler_counters = Some((counters, now));
+
+        let cpu_times = proc_stat.cpus;
+
+        let last_cpu_times = &self.last_cpu_times;
+        let breakdown_of = |name: &str| {
+            let now = cpu_times.get(name)?;
+            let earlier = last_cpu_times.get(name)?;
+            Some(now.breakdown_since(earlier))
+        };
+
+        self.snapshot.cpus_performance_data_points.iter_mut()
+            .filter(|cpu| cpu.removed_since.is_none())
+            .for_each(|cpu| cpu.breakdown = breakdown_of(&cpu.name));
+        if let Some(total) = breakdown_of("cpu") {
+            self.snapshot.cpu_breakdown_data_points.push(total);
+        }
+
+        self.last_cpu_times = cpu_times;
+    }
+
+    fn refresh_memory(&mut self) {
+        let sys = &mut self.system;
+        sys.refresh_memory();
+
+        let memory = (sys.used_memory() as f64 / sys.total_memory().max(1) as f64) * 100.0;
+
+        self.snapshot.memory_usage = sys.used_memory();
+        self.snapshot.swap_usage = sys.used_swap();
+        self.snapshot.total_swap = sys.total_swap();
+        self.snapshot.memory_usage_data_points.push(memory as f32);
+        if sys.total_swap() > 0 {
+            let swap = (sys.used_swap() as f64 / sys.total_swap() as f64) * 100.0;
+            self.snapshot.swap_usage_data_points.push(swap as f32);
+        }
+
+        #[cfg(target_os = "linux")]
+        self.refresh_vmstat();
+
+        #[cfg(target_os = "linux")]
+        match meminfo::read() {
+            Ok(breakdown) => self.snapshot.memory_breakdown_data_points.push(breakdown),
+            Err(e) => self.snapshot.error_log.push(e),
+        }
+        #[cfg(target_os = "linux")]
+        self.refresh_pressure(PressureResource::Memory);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn refresh_vmstat(&mut self) {
+        let counters = match vmstat::read() {
+            Ok(value) => value,
+            Err(e) => {
+                self.snapshot.error_log.push(e);
+                return;
+            }
+        };
+
+        let now = Instant::now();
+        if let Some((last, last_time)) = self.last_vmstat {
+            let elapsed_time = now.duration_since(last_time).as_secs_f64();
+            if elapsed_time > 0.0 {
+                let per_second = |now: u64, before: u64| (now.saturating_sub(before) as f64 / elapsed_time) as f32;
+                let major_faults = per_second(counters.major_page_faults, last.major_page_faults);
+                let paging = &mut self.snapshot.paging_informations;
+                paging.swap_in_per_second.push(per_second(counters.swap_in_pages, last.swap_in_pages));
+                paging.swap_out_per_second.push(per_second(counters.swap_out_pages, last.swap_out_pages));
+                paging.major_faults_per_second.push(major_faults);
+                paging.minor_faults_per_second.push((per_second(counters.page_faults, last.page_faults) - major_faults).max(0.0));
+            }
+        }
+        self.last_vmstat = Some((counters, now));
+    }
+
+    /// Major faults per second of every process since the previous process refresh.
+    /// A process seen for the first time gets no rate yet.
+    #[cfg(target_os = "linux")]
+    fn major_fault_rates(&mut self) -> HashMap<Pid, f32> {
+        let now = Instant::now();
+        let current: HashMap<Pid, u64> = self.system.processes().keys()
+            .filter_map(|pid| Some((*pid, process::major_faults(*pid)?)))
+            .collect();
+
+        let rates = match &self.last_major_faults {
+            Some((last, last_time)) => {
+                let elapsed_time = now.duration_since(*last_time).as_secs_f64().max(f64::EPSILON);
+                current.iter().filter_map(|(pid, faults)|{
+                    let before = last.get(pid)?;
+                    Some((*pid, (faults.saturating_sub(*before) as f64 / elapsed_time) as f32))
+                }).collect()
+            }
+            None => HashMap::new(),
+        };
+        self.last_major_faults = Some((current, now));
+        rates
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn major_fault_rates(&mut self) -> HashMap<Pid, f32> {
+        HashMap::new()
+    }
+
+    /// tcp bytes per second of every process owning a socket, from the sockets' own counters.
+    /// A socket opened since the previous refresh counts from zero, one closed in between is lost.
+    #[cfg(target_os = "linux")]
+    fn network_rates(&mut self) -> HashMap<Pid, f32> {
+        let now = Instant::now();
+        let current = match sock_diag::tcp_byte_counters() {
+            Ok(value) => value,
+            Err(e) => {
+                self.snapshot.error_log.push(MonitorError::SocketDiagUnavailable { reason: e.to_string() });
+                self.last_socket_bytes = None;
+                return HashMap::new();
+            }
+        };
+
+        let mut rates = HashMap::new();
+        if self.last_socket_bytes.is_some() {
+            self.update_socket_owners(current.keys());
+        }
+        if let Some((last, last_time)) = &self.last_socket_bytes {
+            let elapsed_time = now.duration_since(*last_time).as_secs_f64().max(f64::EPSILON);
+            current.iter().for_each(|(inode, bytes)|{
+                let pid = match self.socket_owners.get(inode) {
+                    Some(value) => *value,
+                    None => return,
+                };
+                let before = last.get(inode).copied().unwrap_or_default();
+                let moved = bytes.sent.saturating_sub(before.sent) + bytes.received.saturating_sub(before.received);
+                *rates.entry(pid).or_insert(0.0) += (moved as f64 / elapsed_time) as f32;
+            });
+        }
+        self.last_socket_bytes = Some((current, now));
+        rates
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn network_rates(&mut self) -> HashMap<Pid, f32> {
+        HashMap::new()
+    }
+
+    /// Walking every `/proc/<pid>/fd` is slow, so the owners are only looked up again when one of
+    /// the sockets is unknown, and at most once per refresh. Inode 0 is a socket without a descriptor (TIME_WAIT).
+    #[cfg(target_os = "linux")]
+    fn update_socket_owners<'a>(&mut self, inodes: impl IntoIterator<Item = &'a u64>) {
+        if self.socket_owners_rebuilt {
+            return;
+        }
+        if inodes.into_iter().any(|inode| *inode != 0 && !self.socket_owners.contains_key(inode)) {
+            self.socket_owners = process::socket_owners();
+            self.socket_owners_rebuilt = true;
+        }
+    }
+
+    /// The I/O view: storage bytes, cancelled writes and syscalls per second and the I/O priority of
+    /// every process whose `/proc/<pid>/io` is readable.
+    #[cfg(target_os = "linux")]
+    fn refresh_process_io(&mut self) {
+        let now = Instant::now();
+        let processes = self.system.processes();
+        let current: HashMap<Pid, IoCounters> = processes.keys()
+            .filter_map(|pid| Some((*pid, process::io_counters(*pid)?)))
+            .collect();
+
+        let mut process_io = match &self.last_process_io {
+            Some((last, last_time)) => process_io_rates(last, &current, now.duration_since(*last_time).as_secs_f64()),
+            None => process_io_rates(&HashMap::new(), &current, 0.0),
+        };
+        process_io.iter_mut().for_each(|x|{
+            x.name = processes.get(&x.pid).map(|x| x.name().to_string()).unwrap_or_default();
+            x.priority = process::io_priority(x.pid).ok();
+        });
+        process_io.sort_by(|a, b| a.bytes_per_second().total_cmp(&b.bytes_per_second()).reverse().then(a.pid.cmp(&b.pid)));
+
+        self.snapshot.process_io = Ok(process_io);
+        self.last_process_io = Some((current, now));
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn refresh_process_io(&mut self) {
+        self.snapshot.process_io = Err(String::from("per process I/O is only read on linux"));
+    }
+
+    fn refresh_processes(&mut self) {
+        self.system.refresh_processes();
+        let now = Instant::now();
+        // sysinfo counts the disk bytes since its previous refresh
+        let disk_elapsed_time = self.last_process_refresh.map(|x| now.duration_since(x).as_secs_f64().max(f64::EPSILON));
+        self.last_process_refresh = Some(now);
+        let major_fault_rates = self.major_fault_rates();
+        let network_rates = &self.process_network_rates;
+        let sys = &self.system;
+
+        let core_count = match sys.physical_core_count() {
+            Some(value) => value,
+            None => {
+                self.snapshot.error_log.push(MonitorError::PhysicalCoreCountUnavailable);
+                sys.cpus().len().max(1)
+            }
+        };
+
+        self.snapshot.process_informations = sys.processes()
+            .iter()
+            .into_group_map_by(|x| match x.1.parent() {
+                Some(value) => { value }
+                None => Pid::from(rand::thread_rng().gen_range(10_000..100_000))
+            }).into_iter().map(|x|{
+                let inner_vec: Vec<SecificProcess> = x.1.iter().map(|col|{
+                    SecificProcess {
+                        pid: *col.0,
+                        name: col.1.name().to_string(),
+                        cpu: (col.1.cpu_usage() / core_count as f32),
+                        memory: col.1.memory() as f32 / 1_048_576.0,
+                        disk: match disk_elapsed_time {
+                            Some(elapsed_time) => ((col.1.disk_usage().read_bytes + col.1.disk_usage().written_bytes) as f64 / elapsed_time) as f32,
+                            None => 0.0,
+                        },
+                        major_faults_per_second: major_fault_rates.get(col.0).copied().unwrap_or(0.0),
+                        network: network_rates.get(col.0).copied().unwrap_or(0.0) }
+                }).collect();
+
+                let cpu: f32 = inner_vec.iter().map(|y| y.cpu).sum();
+                let name = inner_vec.iter().max_by_key(|x| x.memory as i32).map(|x| x.name.clone()).unwrap_or_default();
+
+                (x.0, ProcessInformations {
+                    pid: x.0,
+                    name,
+                    cpu,
+                    memory: inner_vec.iter().map(|y| y.memory).sum(),
+                    disk: inner_vec.iter().map(|y| y.disk).sum(),
+                    major_faults_per_second: inner_vec.iter().map(|y| y.major_faults_per_second).sum(),
+                    network: inner_vec.iter().map(|y| y.network).sum(),
+                    child_processes: inner_vec,
+                })
+            }).collect();
+
+        self.refresh_process_details();
+    }
+
+    fn refresh_process_network(&mut self) {
+        self.process_network_rates = self.network_rates();
+        if let Some(pid) = self.inspected_process {
+            self.inspected_network_history.push(self.process_network_rates.get(&pid).copied().unwrap_or(0.0));
+        }
+    }
+
+    fn refresh_process_details(&mut self) {
+        let pid = match self.inspected_process {
+            Some(value) => value,
+            None => {
+                self.snapshot.process_details = None;
+                return;
+            }
+        };
+        let name = match self.system.process(pid) {
+            Some(process) => process.name().to_string(),
+            None => {
+                self.snapshot.process_details = None;
+                return;
+            }
+        };
+
+        self.snapshot.process_details = Some(ProcessDetails {
+            pid,
+            name,
+            memory: self.process_memory(pid),
+            open_files: open_files(pid),
+            threads: self.threads(pid),
+            network_plot_points: self.inspected_network_history.clone(),
+        });
+    }
+
+    /// Threads of `pid` with their cpu usage since the previous refresh, busiest first.
+    #[cfg(target_os = "linux")]
+    fn threads(&mut self, pid: Pid) -> Result<Vec<ThreadInformations>, String> {
+        let now = Instant::now();
+        let threads = process::threads(pid)?;
+        let (last, elapsed_time) = match &self.last_thread_ticks {
+            Some((last, last_time)) => (Some(last), now.duration_since(*last_time).as_secs_f64().max(f64::EPSILON)),
+            None => (None, 1.0),
+        };
+
+        let threads_informations = threads.iter().map(|thread|{
+            let cpu = match last.and_then(|x| x.get(&thread.tid)) {
+                Some(before) => (thread.cpu_ticks.saturating_sub(*before) as f64 / process::USER_HZ / elapsed_time * 100.0) as f32,
+                None => 0.0,
+            };
+            ThreadInformations {
+                tid: thread.tid,
+                name: thread.name.clone(),
+                state: thread.state,
+                cpu,
+                last_cpu: thread.last_cpu,
+                voluntary_switches: thread.voluntary_switches,
+                involuntary_switches: thread.involuntary_switches,
+            }
+        })
+        .sorted_by(|a, b| b.cpu.total_cmp(&a.cpu))
+        .collect();
+
+        self.last_thread_ticks = Some((threads.iter().map(|x| (x.tid, x.cpu_ticks)).collect(), now));
+        Ok(threads_informations)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn threads(&mut self, _pid: Pid) -> Result<Vec<ThreadInformations>, String> {
+        Err(String::from("threads are only read on linux"))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn refresh_connections(&mut self) {
+        let sockets = net::read_sockets();
+        self.update_socket_owners(sockets.iter().map(|x| &x.inode));
+        let processes = self.system.processes();
+        let connections = sockets.into_iter().map(|socket|{
+            let pid = self.socket_owners.get(&socket.inode).copied();
+            ConnectionInformations {
+                protocol: socket.protocol,
+                local: socket.local,
+                remote: socket.remote,
+                state: socket.state_name(),
+                pid,
+                process_name: pid.and_then(|x| processes.get(&x)).map(|x| x.name().to_string()).unwrap_or_default(),
+                send_queue: socket.send_queue,
+                receive_queue: socket.receive_queue,
+            }
+        }).collect();
+        self.snapshot.connections = Ok(connections);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn refresh_connections(&mut self) {
+        self.snapshot.connections = Err(String::from("connections are only read on linux"));
+    }
+
+    /// The tree is only walked again when a node, its size or the mount table changed, in between
+    /// only the counters of its nodes are read.
+    #[cfg(target_os = "linux")]
+    fn refresh_block_devices(&mut self) {
+        let stamp = blockdev::stamp();
+        if self.block_topology_stamp.as_ref() != Some(&stamp) {
+            self.snapshot.block_devices = blockdev::topology().map_err(|e| format!("cannot read /sys/block: {}", e));
+            self.block_topology_stamp = self.snapshot.block_devices.is_ok().then_some(stamp);
+        }
+
+        let now = Instant::now();
+        let mut measurements = HashMap::new();
+        if let Ok(devices) = &mut self.snapshot.block_devices {
+            set_block_device_io(devices, &self.last_block_counters, &mut measurements, now);
+        }
+        self.last_block_counters = measurements;
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn refresh_block_devices(&mut self) {
+        self.snapshot.block_devices = Err(String::from("the block device tree is only read on linux"));
+    }
+
+    #[cfg(target_os = "linux")]
+    fn find_open_file(&self, query: &str) -> Result<Vec<OpenFileMatch>, String> {
+        let processes = self.system.processes();
+        Ok(process::find_open_file(processes.keys().copied(), query).into_iter().map(|(pid, file)|{
+            OpenFileMatch {
+                pid,
+                process_name: processes.get(&pid).map(|x| x.name().to_string()).unwrap_or_default(),
+                file,
+            }
+        }).collect())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn find_open_file(&self, _query: &str) -> Result<Vec<OpenFileMatch>, String> {
+        Err(String::from("open files are only read on linux"))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn process_memory(&mut self, pid: Pid) -> Result<ProcessMemory, String> {
+        let total = process::smaps_rollup(pid)?;
+        let mut mapping_groups = process::mapping_groups(pid)?;
+        let baseline = &mut self.mapping_baseline;
+        mapping_groups.iter_mut().for_each(|group|{
+            let initial = *baseline.entry(group.name.clone()).or_insert(group.counters.rss);
+            group.rss_growth = group.counters.rss as i64 - initial as i64;
+        });
+        Ok(ProcessMemory { total, mapping_groups })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn process_memory(&mut self, _pid: Pid) -> Result<ProcessMemory, String> {
+        Err(String::from("memory maps are only read on linux"))
+    }
+
+    fn refresh_disk_io(&mut self) {
+        let config = &self.snapshot.config;
+        let disk_y_min_bound = config.plots.disk_y_min_bound;
+        let error_log = &mut self.snapshot.error_log;
+        let disks = &mut self.snapshot.disks_informations;
+
+        self.system.refresh_disks_list();
+        hotplug::reconcile(disks, self.system.disks(), DiskId::of, |x| disk_informations(x, config, error_log), Instant::now(), config.removed_device_retention());
+
+        disks.iter_mut().filter(|disk| disk.removed_since.is_none()).for_each(|disk|{
+            if let Err(e) = set_disk_transfer(disk) {
+                error_log.push(e);
+            }
+            disk.y_max_bound = disk.io_data_points.data_iter()
+                .map(|x| x.read_kb_per_second.max(x.write_kb_per_second))
+                .fold(disk_y_min_bound, f32::max);
+        });
+
+        #[cfg(target_os = "linux")]
+        self.refresh_pressure(PressureResource::Io);
+    }
+
+    fn refresh_disk_space(&mut self) {
+        self.system.refresh_disks();
+        let now = Instant::now();
+        let disks = &mut self.snapshot.disks_informations;
+        self.system.disks().iter().for_each(|x|{
+            let id = DiskId::of(x);
+            if let Some(disk) = disks.iter_mut().find(|disk| disk.id == id && disk.removed_since.is_none()) {
+                record_space_usage(disk, space_usage(x), now);
+            }
+        });
+    }
+
+    fn refresh_sensors(&mut self) {
+        let history_length = self.snapshot.config.sampling.history_length;
+        let retention = self.snapshot.config.removed_device_retention();
+        let sys = &mut self.system;
+        sys.refresh_components_list();
+
+        // sysinfo reports 0 or NaN for limits a chip doesn't expose
+        let limit = |value: f32| if value.is_finite() && value > 0.0 { Some(value) } else { None };
+        #[cfg_attr(not(target_os = "linux"), allow(unused_mut))]
+        let mut readings: Vec<SensorReading> = sys.components().iter().map(|x| SensorReading {
+            id: SensorId { kind: SensorKind::Temperature, label: x.label().to_string() },
+            current: x.temperature(),
+            max: limit(x.max()),
+            critical: x.critical().and_then(limit),
+        }).collect();
+
+        #[cfg(target_os = "linux")]
+        {
+            readings.extend(hwmon::read_fans());
+            readings.extend(self.read_package_power());
+        }
+
+        let sensors = &mut self.snapshot.sensors_informations;
+        hotplug::reconcile(sensors, &readings, |x| x.id.clone(), |x| SensorInformations::new(x, history_length), Instant::now(), retention);
+
+        sensors.iter_mut().for_each(|sensor|{
+            if let Some(reading) = readings.iter().find(|x| x.id == sensor.id) {
+                sensor.current = reading.current;
+                sensor.max = reading.max;
+                sensor.critical = reading.critical;
+                sensor.plot_points.push(reading.current);
+            }
+        });
+    }
+
+    /// Package power needs two energy reads, so nothing is reported on the first refresh.
+    #[cfg(target_os = "linux")]
+    fn read_package_power(&mut self) -> Vec<SensorReading> {
+        let now = Instant::now();
+        let counters = rapl::read_packages();
+        let readings = match &self.last_energy_counters {
+            Some((last, last_time)) => counters.iter().filter_map(|counter|{
+                let earlier = last.iter().find(|x| x.label == counter.label)?;
+                Some(SensorReading {
+                    id: SensorId { kind: SensorKind::Power, label: counter.label.clone() },
+                    current: counter.watts_since(earlier, now.duration_since(*last_time).as_secs_f64())?,
+                    max: None,
+                    critical: None,
+                })
+            }).collect(),
+            None => Vec::new(),
+        };
+        self.last_energy_counters = Some((counters, now));
+        readings
+    }
+
+    fn refresh_network(&mut self) {
+        let now = Instant::now();
+        // sysinfo reports what changed since the previous refresh, the first one has nothing to divide
+        let elapsed_time = self.last_network_refresh.map(|x| now.duration_since(x).as_secs_f64());
+        self.last_network_refresh = Some(now);
+        let history_length = self.snapshot.config.sampling.history_length;
+        let retention = self.snapshot.config.removed_device_retention();
+        let sys = &mut self.system;
+        sys.refresh_networks_list();
+
+        let addresses = interface_addresses();
+        let present: Vec<(InterfaceId, &String, &NetworkData)> = sys.networks().iter()
+            .map(|(name, data)| (interface_id(name), name, data))
+            .sorted_by(|a, b| a.0.cmp(&b.0))
+            .collect();
+        let networks = &mut self.snapshot.network_informations;
+        let mut next_number = networks.iter().map(|x| x.number).max().unwrap_or(0) + 1;
+        hotplug::reconcile(networks, &present, |x| x.0.clone(), |x|{
+            let network = network_informations(next_number, x.0.clone(), x.1, history_length);
+            next_number += 1;
+            network
+        }, Instant::now(), retention);
+        networks.sort_by(|a, b| a.id.cmp(&b.id));
+
+        networks.iter_mut().for_each(|x: &mut NetworkInformations| {
+            if let Some((_, name, data)) = present.iter().find(|y| y.0 == x.id) {
+                // the index outlives a rename, the name shown follows it
+                x.interface_name = name.to_string();
+                x.details = interface_details(name, &addresses);
+                x.mac_address = data.mac_address();
+                x.total_errors_on_received = data.total_errors_on_received();
+                x.total_errors_on_transmitted = data.total_errors_on_transmitted();
+                let drops = interface_drops(&x.interface_name);
+                let moved = InterfaceCounters {
+                    received: data.received(),
+                    transmitted: data.transmitted(),
+                    packets_received: data.packets_received(),
+                    packets_transmitted: data.packets_transmitted(),
+                    errors: data.errors_on_received() + data.errors_on_transmitted(),
+                    drops: drops_since(x.last_drops, drops),
+                };
+                x.last_drops = drops;
+
+                let totals = &mut x.totals;
+                totals.received += moved.received;
+                totals.transmitted += moved.transmitted;
+                totals.packets_received += moved.packets_received;
+                totals.packets_transmitted += moved.packets_transmitted;
+                totals.errors += moved.errors;
+                totals.drops += moved.drops;
+
+                if let Some(rates) = elapsed_time.and_then(|seconds| interface_rates(&moved, seconds)) {
+                    let display = &mut x.network_display;
+                    display.received_plot_points.push(rates.received);
+                    display.transmitted_plot_points.push(rates.transmitted);
+                    display.packets_received_plot_points.push(rates.packets_received as f32);
+                    display.packets_transmitted_plot_points.push(rates.packets_transmitted as f32);
+                    display.errors_plot_points.push(rates.errors as f32);
+                    display.drops_plot_points.push(rates.drops as f32);
+                }
+            }
+        });
+    }
+}
+
+fn disk_informations(x: &Disk, config: &Config, error_log: &mut ErrorLog) -> DiskInformations {
+    let history_length = config.sampling.history_length;
+    let name = if format!("{:?}", x.name()).replace('"', "") != "" {
+        format!("{:?}", x.name()).replace('"', "")
+    } else {
+        String::from("None")
+    };
+    let kind = match x.kind() {
+        DiskKind::SSD => String::from("SSD"),
+        DiskKind::HDD => String::from("HDD"),
+        DiskKind::Unknown(_) => String::from("UNKNOWN")
+    };
+    let file_system = match std::str::from_utf8(x.file_system()) {
+        Ok(value) => {
+            value.to_string()
+        }
+        Err(_) => {
+            error_log.push(MonitorError::InvalidFileSystemName { mount_point: x.mount_point().display().to_string() });
+            String::from_utf8_lossy(x.file_system()).into_owned()
+        }
+    };
+    let is_removable = if x.is_removable() { String::from("yes") } else { String::from("no") };
+    let disk_fields = vec![
+        name,
+        format!("{:?}", x.mount_point()).replace('"', ""),
+        kind,
+        file_system,
+        is_removable,
+    ];
+    let longest_length = disk_fields.iter().map(|s| s.len()).max().unwrap_or(0);
+    let adjusted_disk_fields: Vec<String> = disk_fields
+        .iter()
+        .map(|s| {
+            let padding = " ".repeat(longest_length.saturating_sub(s.len()));
+            format!("{}{}", s, padding)
+        }).collect();
+
+    let mut disk = DiskInformations{
+        id: DiskId::of(x),
+        name: adjusted_disk_fields[0].to_string(),
+        mount_point: adjusted_disk_fields[1].to_string(),
+        kind: adjusted_disk_fields[2].to_string(),
+        file_system: adjusted_disk_fields[3].to_string(),
+        is_removable: adjusted_disk_fields[4].to_string(),
+        space: SpaceUsage::default(),
+        space_history: Data::new(history_length),
+        last_measurement: None,
+        io_data_points: Data::new(history_length),
+        y_max_bound: config.plots.disk_y_min_bound,
+        removed_since: None,
+    };
+    record_space_usage(&mut disk, space_usage(x), Instant::now());
+    disk
+}
+
+fn space_usage(x: &Disk) -> SpaceUsage {
+    SpaceUsage {
+        total: x.total_space(),
+        available: x.available_space(),
+        inodes: inode_usage(x.mount_point()),
+    }
+}
+
+fn record_space_usage(disk: &mut DiskInformations, space: SpaceUsage, now: Instant) {
+    disk.space = space;
+    disk.space_history.push(SpaceSample { taken_at: now, used: space.used() });
+}
+
+fn network_informations(number: usize, id: InterfaceId, interface_name: &str, history_length: usize) -> NetworkInformations {
+    NetworkInformations {
+        id,
+        number,
+        interface_name: interface_name.to_string(),
+        mac_address: MacAddr::UNSPECIFIED,
+        details: InterfaceDetails::default(),
+        network_display: NetworkDisplay::new(history_length),
+        total_errors_on_received: 0,
+        total_errors_on_transmitted: 0,
+        totals: NetworkTotals::default(),
+        last_drops: None,
+        removed_since: None,
+    }
+}
+
+fn set_disk_transfer(disk: &mut DiskInformations) -> Result<(), MonitorError> {
+    let counters = disk_counters(&disk.id)?;
+    record_disk_counters(disk, counters, Instant::now());
+    Ok(())
+}
+
+/// Each disk keeps the time of its own last read, reading the disks one after another takes a while
+/// and a clock shared between them would leave every disk but the first with almost no elapsed time.
+/// After a reset the counters only become the new baseline.
+fn record_disk_counters(disk: &mut DiskInformations, counters: DiskCounters, now: Instant) {
+    if let Some(last) = disk.last_measurement {
+        let elapsed = now.saturating_duration_since(last.taken_at);
+        if elapsed.is_zero() {
+            return;
+        }
+        if let Some(stats) = DiskIoStats::between(&last.counters, &counters, elapsed) {
+            disk.io_data_points.push(stats);
+        }
+    }
+    disk.last_measurement = Some(DiskMeasurement { taken_at: now, counters });
+}
+
+#[cfg(target_os = "linux")]
+fn interface_id(interface_name: &str) -> InterfaceId {
+    match net::interface_index(interface_name) {
+        Some(index) => InterfaceId::Index(index),
+        None => InterfaceId::Name(interface_name.to_string()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn interface_id(interface_name: &str) -> InterfaceId {
+    InterfaceId::Name(interface_name.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn interface_addresses() -> HashMap<String, Vec<InterfaceAddress>> {
+    net::interface_addresses()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn interface_addresses() -> HashMap<String, Vec<InterfaceAddress>> {
+    HashMap::new()
+}
+
+#[cfg(target_os = "linux")]
+fn interface_details(interface_name: &str, addresses: &HashMap<String, Vec<InterfaceAddress>>) -> InterfaceDetails {
+    net::interface_details(interface_name, addresses)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn interface_details(_interface_name: &str, _addresses: &HashMap<String, Vec<InterfaceAddress>>) -> InterfaceDetails {
+    InterfaceDetails::default()
+}
+
+/// What one interface moved since the previous network refresh.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct InterfaceCounters<T> {
+    received: T,
+    transmitted: T,
+    packets_received: T,
+    packets_transmitted: T,
+    /// Receive and transmit errors together.
+    errors: T,
+    drops: T,
+}
+
+/// Per second rates of what the interface moved in `elapsed_time` seconds, `None` without elapsed time.
+fn interface_rates(moved: &InterfaceCounters<u64>, elapsed_time: f64) -> Option<InterfaceCounters<f64>> {
+    if elapsed_time <= 0.0 {
+        return None;
+    }
+    let per_second = |value: u64| value as f64 / elapsed_time;
+    Some(InterfaceCounters {
+        received: per_second(moved.received),
+        transmitted: per_second(moved.transmitted),
+        packets_received: per_second(moved.packets_received),
+        packets_transmitted: per_second(moved.packets_transmitted),
+        errors: per_second(moved.errors),
+        drops: per_second(moved.drops),
+    })
+}
+
+/// The drop counter is read from sysfs rather than handed out as a difference by sysinfo. A counter that
+/// went backwards (the interface was recreated under the same index) or wasn't read before counts nothing.
+fn drops_since(last: Option<u64>, current: Option<u64>) -> u64 {
+    match (current, last) {
+        (Some(current), Some(last)) => current.saturating_sub(last),
+        _ => 0,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_frequency_limits(cpu: &str) -> Option<CpuFrequencyLimits> {
+    cpufreq::read_limits(cpu)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cpu_frequency_limits(_cpu: &str) -> Option<CpuFrequencyLimits> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn interface_drops(interface_name: &str) -> Option<u64> {
+    net::interface_drops(interface_name)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn interface_drops(_interface_name: &str) -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn open_files(pid: Pid) -> Result<OpenFiles, String> {
+    process::open_files(pid)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_files(_pid: Pid) -> Result<OpenFiles, String> {
+    Err(String::from("open files are only read on linux"))
+}
+
+/// Rates of every node against its counters at the previous refresh, a device stacked on
+/// several parents is read once for each of them.
+#[cfg(target_os = "linux")]
+fn set_block_device_io(devices: &mut [BlockDevice], last: &HashMap<String, DiskMeasurement>, measurements: &mut HashMap<String, DiskMeasurement>, now: Instant) {
+    devices.iter_mut().for_each(|device|{
+        if let Some(counters) = blockdev::counters(&device.name) {
+            device.io = last.get(&device.name)
+                .and_then(|last| DiskIoStats::between(&last.counters, &counters, now.saturating_duration_since(last.taken_at)));
+            measurements.insert(device.name.clone(), DiskMeasurement { taken_at: now, counters });
+        }
+        set_block_device_io(&mut device.children, last, measurements, now);
+    });
+}
+
+/// Rates of every pid in `current` against `last`, without name and priority. A pid missing from `last`
+/// (just started, or its `/proc/<pid>/io` just became readable) reads 0 until the next refresh,
+/// its counters since it started don't belong to a single interval.
+#[cfg(target_os = "linux")]
+fn process_io_rates(last: &HashMap<Pid, IoCounters>, current: &HashMap<Pid, IoCounters>, elapsed_time: f64) -> Vec<ProcessIo> {
+    let elapsed_time = elapsed_time.max(f64::EPSILON);
+    current.iter().map(|(pid, counters)|{
+        let before = last.get(pid).unwrap_or(counters);
+        let rate = |current: u64, before: u64| (current.saturating_sub(before) as f64 / elapsed_time) as f32;
+        ProcessIo {
+            pid: *pid,
+            name: String::new(),
+            read_bytes_per_second: rate(counters.read_bytes, before.read_bytes),
+            write_bytes_per_second: rate(counters.write_bytes, before.write_bytes),
+            cancelled_write_bytes_per_second: rate(counters.cancelled_write_bytes, before.cancelled_write_bytes),
+            read_syscalls_per_second: rate(counters.read_syscalls, before.read_syscalls),
+            write_syscalls_per_second: rate(counters.write_syscalls, before.write_syscalls),
+            priority: None,
+        }
+    }).collect()
+}
+
+#[cfg(target_os = "linux")]
+fn set_io_priority(pid: Pid, priority: IoPriority) -> Result<(), String> {
+    process::set_io_priority(pid, priority).map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_io_priority(_pid: Pid, _priority: IoPriority) -> Result<(), String> {
+    Err(String::from("I/O priorities are only supported on linux"))
+}
+
+#[cfg(target_os = "linux")]
+fn inode_usage(mount_point: &Path) -> Option<InodeUsage> {
+    statvfs::inode_usage(mount_point).ok().flatten()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn inode_usage(_mount_point: &Path) -> Option<InodeUsage> {
+    None
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn disk_counters(disk: &DiskId) -> Result<DiskCounters, MonitorError> {
+    Err(MonitorError::DiskPerformanceUnavailable { disk: disk.device.to_string_lossy().into_owned() })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use super::*;
+
+    #[test]
+    fn interface_rates_are_what_moved_per_second() {
+        let moved = InterfaceCounters { received: 3000, transmitted: 1000, packets_received: 30, packets_transmitted: 10, errors: 2, drops: 1 };
+
+        assert_eq!(interface_rates(&moved, 2.0), Some(InterfaceCounters {
+            received: 1500.0,
+            transmitted: 500.0,
+            packets_received: 15.0,
+            packets_transmitted: 5.0,
+            errors: 1.0,
+            drops: 0.5,
+        }));
+    }
+
+    #[test]
+    fn interface_rates_need_elapsed_time() {
+        let moved = InterfaceCounters { received: 3000, ..InterfaceCounters::default() };
+
+        assert_eq!(interface_rates(&moved, 0.0), None);
+        assert_eq!(interface_rates(&InterfaceCounters::default(), 0.0), None);
+    }
+
+    #[test]
+    fn a_reset_or_first_drop_counter_counts_nothing() {
+        assert_eq!(drops_since(Some(10), Some(14)), 4);
+        assert_eq!(drops_since(Some(10), Some(3)), 0);
+        assert_eq!(drops_since(None, Some(3)), 0);
+        assert_eq!(drops_since(Some(10), None), 0);
+    }
+
+    fn disk(device: &str) -> DiskInformations {
+        DiskInformations {
+            id: DiskId { device: OsString::from(device), mount_point: PathBuf::from("/") },
+            name: device.to_string(),
+            mount_point: String::from("/"),
+            space: SpaceUsage::default(),
+            space_history: Data::new(10),
+            kind: String::new(),
+            file_system: String::new(),
+            is_removable: String::new(),
+            last_measurement: None,
+            io_data_points: Data::new(10),
+            y_max_bound: 0.0,
+            removed_since: None,
+        }
+    }
+
+    /// A disk that did `requests` reads of 4 KiB each, every one of them taking 2 ms.
+    fn counters(requests: u64) -> DiskCounters {
+        DiskCounters {
+            bytes_read: requests * 4096,
+            reads: requests,
+            read_time_ms: requests * 2,
+            busy_time_ms: requests * 2,
+            weighted_busy_time_ms: Some(requests * 2),
+            ..DiskCounters::default()
+        }
+    }
+
+    #[test]
+    fn disks_read_one_after_another_keep_their_own_clock() {
+        let mut disks = vec![disk("sda"), disk("sdb"), disk("sdc")];
+        let start = Instant::now();
+        // every read takes 100 ms, the second round starts a second after the first
+        for (round, requests) in [(0, 0), (1, 250)] {
+            disks.iter_mut().enumerate().for_each(|(index, disk)| {
+                let now = start + Duration::from_secs(round) + Duration::from_millis(100 * index as u64);
+                record_disk_counters(disk, counters(requests), now);
+            });
+        }
+
+        disks.iter().for_each(|disk| {
+            let stats = disk.io_data_points.last().unwrap();
+            assert_eq!(stats.read_iops, 250.0, "{}", disk.name);
+            assert_eq!(stats.read_kb_per_second, 1000.0, "{}", disk.name);
+            assert_eq!(stats.await_ms, 2.0, "{}", disk.name);
+            assert_eq!(stats.utilization, 50.0, "{}", disk.name);
+            assert_eq!(stats.queue_depth, 0.5, "{}", disk.name);
+        });
+    }
+
+    #[test]
+    fn a_32_bit_counter_wrap_counts_the_requests_across_it() {
+        let mut disk = disk("sda");
+        let start = Instant::now();
+        let before = DiskCounters { reads: u64::from(u32::MAX) - 9, ..DiskCounters::default() };
+        let after = DiskCounters { reads: 10, ..DiskCounters::default() };
+
+        record_disk_counters(&mut disk, before, start);
+        record_disk_counters(&mut disk, after, start + Duration::from_secs(2));
+
+        assert_eq!(disk.io_data_points.last().unwrap().read_iops, 10.0);
+    }
+
+    #[test]
+    fn a_reset_only_starts_a_new_baseline() {
+        let mut disk = disk("sda");
+        let start = Instant::now();
+
+        record_disk_counters(&mut disk, counters(1 << 40), start);
+        record_disk_counters(&mut disk, counters(100), start + Duration::from_secs(1));
+        assert!(disk.io_data_points.last().is_none());
+
+        record_disk_counters(&mut disk, counters(300), start + Duration::from_secs(2));
+        assert_eq!(disk.io_data_points.last().unwrap().read_iops, 200.0);
+    }
+
+    #[test]
+    fn steady_growth_projects_when_the_filesystem_fills_up() {
+        const GIB: u64 = 1 << 30;
+        let mut disk = disk("sda");
+        let start = Instant::now();
+        // 1 GiB more every 10 minutes on a 100 GiB filesystem
+        (0..6).for_each(|sample| {
+            let used = 40 * GIB + sample * GIB;
+            let space = SpaceUsage { total: 100 * GIB, available: 100 * GIB - used, inodes: None };
+            record_space_usage(&mut disk, space, start + Duration::from_secs(600 * sample));
+        });
+
+        let growth_rate = disk.growth_rate().unwrap();
+        assert!((growth_rate - GIB as f64 / 600.0).abs() < 1.0, "{}", growth_rate);
+        let hours = disk.time_to_full().unwrap().as_secs_f64() / 3600.0;
+        // 55 GiB left at 6 GiB an hour
+        assert!((hours - 55.0 / 6.0).abs() < 0.01, "{}", hours);
+    }
+
+    #[test]
+    fn shrinking_or_unchanged_usage_is_never_projected_full() {
+        let mut disk = disk("sda");
+        let start = Instant::now();
+        [(0, 500), (60, 400), (120, 400)].iter().for_each(|&(seconds, used)| {
+            let space = SpaceUsage { total: 1000, available: 1000 - used, inodes: None };
+            record_space_usage(&mut disk, space, start + Duration::from_secs(seconds));
+        });
+
+        assert!(disk.time_to_full().is_none());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn a_process_without_a_previous_io_sample_reads_zero() {
+        use sysinfo::PidExt;
+        let counters = |bytes: u64| IoCounters { read_bytes: bytes, write_bytes: bytes, read_syscalls: bytes, ..IoCounters::default() };
+        let old = Pid::from_u32(10);
+        let new = Pid::from_u32(20);
+        let last = HashMap::from([(old, counters(1000))]);
+        // the new pid has been running for days, its io only just became readable
+        let current = HashMap::from([(old, counters(3000)), (new, counters(1 << 40))]);
+
+        let rates = process_io_rates(&last, &current, 2.0);
+
+        let rate_of = |pid: Pid| rates.iter().find(|x| x.pid == pid).unwrap();
+        assert_eq!(rate_of(old).read_bytes_per_second, 1000.0);
+        assert_eq!(rate_of(old).read_syscalls_per_second, 1000.0);
+        assert_eq!(rate_of(new).bytes_per_second(), 0.0);
+        assert_eq!(rate_of(new).read_syscalls_per_second, 0.0);
+    }
+
+    #[test]
+    fn a_read_without_elapsed_time_is_ignored() {
+        let mut disk = disk("sda");
+        let start = Instant::now();
+
+        record_disk_counters(&mut disk, counters(0), start);
+        record_disk_counters(&mut disk, counters(10), start);
+        record_disk_counters(&mut disk, counters(20), start + Duration::from_secs(1));
+
+        assert_eq!(disk.io_data_points.last().unwrap().read_iops, 20.0);
+    }
+}
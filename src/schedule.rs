@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::config::Config;
+
+/// Shortest nap of the sampler thread, so a burst of due subsystems can't turn the loop into a busy wait.
+const MIN_SLEEP: Duration = Duration::from_millis(10);
+
+/// Parts of the system that are refreshed independently of each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Subsystem {
+    Cpu,
+    Memory,
+    Processes,
+    DiskIo,
+    DiskSpace,
+    Network,
+    Sensors,
+    Connections,
+    /// `/proc/<pid>/io` and the I/O priority of every process, for the "disk I/O by process" view.
+    ProcessIo,
+    /// tcp bytes per process for the NET column, a sock_diag dump plus a walk over every descriptor.
+    ProcessNetwork,
+    /// The block device tree and the I/O of each of its nodes.
+    BlockDevices,
+}
+
+impl Subsystem {
+    /// In refresh order, `Processes` takes the rates of `ProcessNetwork` and `ProcessIo` its process list.
+    pub const ALL: [Subsystem; 11] = [
+        Subsystem::Cpu,
+        Subsystem::Memory,
+        Subsystem::ProcessNetwork,
+        Subsystem::Processes,
+        Subsystem::ProcessIo,
+        Subsystem::DiskIo,
+        Subsystem::BlockDevices,
+        Subsystem::DiskSpace,
+        Subsystem::Network,
+        Subsystem::Sensors,
+        Subsystem::Connections,
+    ];
+}
+
+/// Views inside a panel that the UI reports as open, the subsystems only they need are sampled meanwhile.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpenViews {
+    pub process_io: bool,
+    /// The process list is sorted by NET or a process's network tab is open.
+    pub process_network: bool,
+    pub block_devices: bool,
+}
+
+impl OpenViews {
+    pub fn shows(&self, subsystem: Subsystem) -> bool {
+        match subsystem {
+            Subsystem::ProcessIo => self.process_io,
+            Subsystem::ProcessNetwork => self.process_network,
+            Subsystem::BlockDevices => self.block_devices,
+            _ => true,
+        }
+    }
+}
+
+/// Tracks when every subsystem was refreshed last. Intervals are read from the config on every call,
+/// so a reloaded config takes effect on the next tick.
+pub struct RefreshSchedule {
+    last_refresh: HashMap<Subsystem, Instant>,
+}
+
+impl RefreshSchedule {
+    pub fn new() -> Self {
+        Self {
+            last_refresh: HashMap::new(),
+        }
+    }
+
+    /// Subsystems whose interval has elapsed. They are marked as refreshed at `now`.
+    /// Subsystems whose panel or view is hidden are never due.
+    pub fn take_due(&mut self, now: Instant, config: &Config, views: &OpenViews) -> Vec<Subsystem> {
+        let due: Vec<Subsystem> = Subsystem::ALL
+            .iter()
+            .copied()
+            .filter(|subsystem| config.is_sampled(*subsystem) && views.shows(*subsystem))
+            .filter(|subsystem| match self.last_refresh.get(subsystem) {
+                Some(last) => now.duration_since(*last) >= config.refresh_interval(*subsystem),
+                None => true,
+            })
+            .collect();
+
+        due.iter().for_each(|subsystem| {
+            self.last_refresh.insert(*subsystem, now);
+        });
+        due
+    }
+
+    /// How long the sampler can sleep before the next subsystem becomes due.
+    pub fn time_until_next(&self, now: Instant, config: &Config, views: &OpenViews) -> Duration {
+        Subsystem::ALL
+            .iter()
+            .filter(|subsystem| config.is_sampled(**subsystem) && views.shows(**subsystem))
+            .map(|subsystem| match self.last_refresh.get(subsystem) {
+                Some(last) => (*last + config.refresh_interval(*subsystem)).saturating_duration_since(now),
+                None => Duration::ZERO,
+            })
+            .min()
+            .unwrap_or_else(|| config.interval())
+            .max(MIN_SLEEP)
+    }
+}
+
+impl Default for RefreshSchedule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::PanelsConfig;
+    use super::*;
+
+    const ALL_VIEWS: OpenViews = OpenViews { process_io: true, process_network: true, block_devices: true };
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.sampling.interval_ms = 1000;
+        config.sampling.cpu_interval_ms = Some(250);
+        config.sampling.disk_space_interval_ms = Some(5000);
+        config
+    }
+
+    #[test]
+    fn every_subsystem_is_due_on_its_own_interval() {
+        let config = config();
+        let mut schedule = RefreshSchedule::new();
+        let now = Instant::now();
+
+        assert_eq!(schedule.take_due(now, &config, &ALL_VIEWS), Subsystem::ALL);
+        assert!(schedule.take_due(now + Duration::from_millis(249), &config, &ALL_VIEWS).is_empty());
+        assert_eq!(schedule.take_due(now + Duration::from_millis(250), &config, &ALL_VIEWS), [Subsystem::Cpu]);
+
+        let due = schedule.take_due(now + Duration::from_millis(1000), &config, &ALL_VIEWS);
+        assert_eq!(due.len(), Subsystem::ALL.len() - 1);
+        assert!(!due.contains(&Subsystem::DiskSpace));
+        assert_eq!(schedule.take_due(now + Duration::from_millis(5000), &config, &ALL_VIEWS).len(), Subsystem::ALL.len());
+    }
+
+    #[test]
+    fn hidden_panels_and_closed_views_are_never_due() {
+        let mut config = config();
+        config.panels.sensors = false;
+        let mut schedule = RefreshSchedule::new();
+        let now = Instant::now();
+
+        let due = schedule.take_due(now, &config, &OpenViews::default());
+        assert!(!due.contains(&Subsystem::Sensors));
+        assert!(!due.contains(&Subsystem::ProcessIo));
+        assert!(!due.contains(&Subsystem::ProcessNetwork));
+        assert!(!due.contains(&Subsystem::BlockDevices));
+        assert!(due.contains(&Subsystem::Processes));
+
+        // a view opened later is due right away, not after a full interval
+        let views = OpenViews { process_io: true, ..OpenViews::default() };
+        assert_eq!(schedule.take_due(now + Duration::from_millis(1), &config, &views), [Subsystem::ProcessIo]);
+    }
+
+    #[test]
+    fn the_sampler_sleeps_until_the_next_due_subsystem() {
+        let config = config();
+        let mut schedule = RefreshSchedule::new();
+        let now = Instant::now();
+
+        assert_eq!(schedule.time_until_next(now, &config, &ALL_VIEWS), MIN_SLEEP);
+        schedule.take_due(now, &config, &ALL_VIEWS);
+        assert_eq!(schedule.time_until_next(now, &config, &ALL_VIEWS), Duration::from_millis(250));
+        assert_eq!(schedule.time_until_next(now + Duration::from_millis(100), &config, &ALL_VIEWS), Duration::from_millis(150));
+    }
+
+    #[test]
+    fn the_sleep_never_drops_below_the_floor() {
+        let config = config();
+        let mut schedule = RefreshSchedule::new();
+        let now = Instant::now();
+        schedule.take_due(now, &config, &ALL_VIEWS);
+
+        assert_eq!(schedule.time_until_next(now + Duration::from_millis(245), &config, &ALL_VIEWS), MIN_SLEEP);
+        assert_eq!(schedule.time_until_next(now + Duration::from_secs(10), &config, &ALL_VIEWS), MIN_SLEEP);
+    }
+
+    #[test]
+    fn with_every_panel_hidden_the_sampler_sleeps_one_interval() {
+        let mut config = config();
+        config.panels = PanelsConfig { cpu: false, network: false, memory: false, disks: false, processes: false, sensors: false, connections: false };
+        let mut schedule = RefreshSchedule::new();
+        let now = Instant::now();
+
+        assert!(schedule.take_due(now, &config, &ALL_VIEWS).is_empty());
+        assert_eq!(schedule.time_until_next(now, &config, &ALL_VIEWS), Duration::from_millis(1000));
+    }
+}
@@ -0,0 +1,1217 @@
+use std::collections::{VecDeque, HashMap};
+use std::ffi::OsString;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use egui::Color32;
+use egui::epaint::Hsva;
+use sysinfo::{MacAddr, Cpu, CpuExt, Disk, DiskExt, Pid};
+use crate::config::{Config, ProbeConfig, ProbeKind, ThresholdsConfig};
+use crate::error::ErrorLog;
+use crate::hotplug::Device;
+
+/// Everything the UI draws, published by the sampler thread after each refresh.
+/// A snapshot is never mutated once sent, the UI just swaps in the newest one.
+#[derive(Clone)]
+pub struct Snapshot {
+    pub total_cpu_usage: u64,
+    pub memory_usage: u64,
+    pub swap_usage: u64,
+    /// 0 when no swap is configured, `swap_usage_data_points` stays empty then.
+    pub total_swap: u64,
+    pub network_informations: Vec<NetworkInformations>,
+    pub cpu_performance_data_points: Data<f32>,
+    /// Empty where the platform has no per-state accounting.
+    pub cpu_breakdown_data_points: Data<CpuBreakdown>,
+    pub cpus_performance_data_points: Vec<CpuData>,
+    pub memory_usage_data_points: Data<f32>,
+    /// Empty where the platform has no `/proc/meminfo`.
+    pub memory_breakdown_data_points: Data<MemoryBreakdown>,
+    pub paging_informations: PagingInformations,
+    pub disks_informations: Vec<DiskInformations>,
+    pub swap_usage_data_points: Data<f32>,
+    pub process_informations: HashMap<Pid, ProcessInformations>,
+    /// Only collected for the process open in the details window.
+    pub process_details: Option<ProcessDetails>,
+    /// Result of the last "who has this file open" search.
+    pub open_file_search: Option<OpenFileSearch>,
+    /// The error says why there are none, e.g. the platform.
+    pub connections: Result<Vec<ConnectionInformations>, String>,
+    /// Storage I/O of every readable process, busiest first.
+    pub process_io: Result<Vec<ProcessIo>, String>,
+    /// Disks, partitions and what is stacked on them, refreshed with the disk I/O.
+    pub block_devices: Result<Vec<BlockDevice>, String>,
+    /// In the order of `config.probes`.
+    pub probes: Vec<ProbeInformations>,
+    pub scheduler_informations: SchedulerInformations,
+    pub sensors_informations: Vec<SensorInformations>,
+    pub cpu_pressure: PressureInformations,
+    pub memory_pressure: PressureInformations,
+    pub io_pressure: PressureInformations,
+    pub config: Config,
+    pub config_error: Option<String>,
+    pub error_log: ErrorLog,
+}
+
+/// Records of a history are stored in chunks of this many, only the unfinished chunk is copied on publish.
+const DATA_CHUNK_LENGTH: usize = 32;
+
+/// A bounded history. Full chunks are immutable and shared by the sampler and every snapshot it published,
+/// so publishing costs a pointer per chunk plus the records of the unfinished one, whatever the history length.
+#[derive(Clone)]
+pub struct Data<T>{
+    data_points: usize,
+    chunks: VecDeque<Arc<[T]>>,
+    tail: Vec<T>,
+    /// Leading records of `chunks` (or of `tail` while there are none) that fell out of the history.
+    skip: usize,
+}
+
+impl<T> Data<T> {
+    pub fn new(data_points: usize) -> Self {
+        Self {
+            data_points,
+            chunks: VecDeque::new(),
+            tail: Vec::with_capacity(DATA_CHUNK_LENGTH),
+            skip: 0,
+        }
+    }
+
+    pub fn data_iter(&self) -> impl Iterator<Item = &T> {
+        self.chunks.iter().flat_map(|chunk| chunk.iter()).chain(self.tail.iter()).skip(self.skip)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.tail.last().or_else(|| self.chunks.back()?.last())
+    }
+
+    pub fn push(&mut self, data_record: T) {
+        self.tail.push(data_record);
+        if self.tail.len() == DATA_CHUNK_LENGTH {
+            let chunk = std::mem::replace(&mut self.tail, Vec::with_capacity(DATA_CHUNK_LENGTH));
+            self.chunks.push_back(chunk.into());
+        }
+        self.trim();
+    }
+
+    pub fn set_data_points(&mut self, data_points: usize) {
+        self.data_points = data_points;
+        self.trim();
+    }
+
+    fn len(&self) -> usize {
+        self.chunks.len() * DATA_CHUNK_LENGTH + self.tail.len() - self.skip
+    }
+
+    /// Keeps `data_points + 1` records, a chunk is dropped once all of its records are skipped.
+    fn trim(&mut self) {
+        self.skip += self.len().saturating_sub(self.data_points + 1);
+        while self.skip >= DATA_CHUNK_LENGTH && !self.chunks.is_empty() {
+            self.chunks.pop_front();
+            self.skip -= DATA_CHUNK_LENGTH;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CpuData{
+    pub name: String,
+    pub usage: f32,
+    pub color: Color32,
+    pub plot_points: Data<f32>,
+    /// When each of `plot_points` was taken.
+    pub sample_times: Data<SystemTime>,
+    pub breakdown: Option<CpuBreakdown>,
+    /// Current frequency in MHz.
+    pub frequency: u64,
+    pub frequency_plot_points: Data<f32>,
+    pub frequency_limits: Option<CpuFrequencyLimits>,
+    pub removed_since: Option<Instant>,
+}
+
+/// What cpufreq lets the core run at. Only collected on linux.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CpuFrequencyLimits {
+    pub min_mhz: u64,
+    pub max_mhz: u64,
+    pub governor: String,
+}
+
+/// Load and scheduler activity of the whole machine. The rates are per second since the previous cpu refresh.
+/// Everything except the load average comes from `/proc/stat`, windows reports a load average of zero.
+#[derive(Clone)]
+pub struct SchedulerInformations {
+    pub load_average_one: Data<f32>,
+    pub load_average_five: Data<f32>,
+    pub load_average_fifteen: Data<f32>,
+    pub context_switches_per_second: Data<f32>,
+    pub interrupts_per_second: Data<f32>,
+    pub forks_per_second: Data<f32>,
+    pub procs_running: Data<f32>,
+    pub procs_blocked: Data<f32>,
+}
+
+impl SchedulerInformations {
+    pub fn new(history_length: usize) -> Self {
+        Self {
+            load_average_one: Data::new(history_length),
+            load_average_five: Data::new(history_length),
+            load_average_fifteen: Data::new(history_length),
+            context_switches_per_second: Data::new(history_length),
+            interrupts_per_second: Data::new(history_length),
+            forks_per_second: Data::new(history_length),
+            procs_running: Data::new(history_length),
+            procs_blocked: Data::new(history_length),
+        }
+    }
+
+    pub fn set_data_points(&mut self, history_length: usize) {
+        [
+            &mut self.load_average_one,
+            &mut self.load_average_five,
+            &mut self.load_average_fifteen,
+            &mut self.context_switches_per_second,
+            &mut self.interrupts_per_second,
+            &mut self.forks_per_second,
+            &mut self.procs_running,
+            &mut self.procs_blocked,
+        ].into_iter().for_each(|x| x.set_data_points(history_length));
+    }
+}
+
+/// Where a cpu's time went over the last interval, in percent. Only collected on linux.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CpuBreakdown {
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub idle: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,
+    pub guest: f32,
+}
+
+impl CpuBreakdown {
+    /// Busy states in the order they are stacked on the plot, bottom first. Idle is whatever is left.
+    pub fn stacked(&self) -> [(&'static str, f32); 8] {
+        [
+            ("user", self.user),
+            ("nice", self.nice),
+            ("system", self.system),
+            ("irq", self.irq),
+            ("softirq", self.softirq),
+            ("iowait", self.iowait),
+            ("steal", self.steal),
+            ("guest", self.guest),
+        ]
+    }
+}
+
+impl fmt::Display for CpuBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in self.stacked() {
+            writeln!(f, "{}: {:.1}%", name, value)?;
+        }
+        write!(f, "idle: {:.1}%", self.idle)
+    }
+}
+
+impl CpuData {
+    pub fn new(cpus: &[Cpu], initial_auto_color_index: usize, history_length: usize) -> Vec<Self> {
+        let mut i = initial_auto_color_index;
+        let data = cpus.iter().map(|x|{
+            let cpu = Self::from_cpu(x, i, history_length);
+            i += 2;
+            cpu
+        }).collect();
+
+        data
+    }
+
+    pub fn from_cpu(cpu: &Cpu, auto_color_index: usize, history_length: usize) -> Self {
+        Self {
+            name: cpu.name().to_string(),
+            usage: cpu.cpu_usage(),
+            plot_points: Data::new(history_length),
+            sample_times: Data::new(history_length),
+            color: Self::auto_color(auto_color_index),
+            breakdown: None,
+            frequency: cpu.frequency(),
+            frequency_plot_points: Data::new(history_length),
+            frequency_limits: None,
+            removed_since: None,
+        }
+    }
+
+    fn auto_color(i: usize) -> Color32 {
+        let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0;
+        let h = i as f32 * golden_ratio;
+        Hsva::new(h, 0.85, 0.5, 1.0).into()
+    }
+}
+
+/// Device name plus mount point, so two mounts of the same device and a re-plugged stick mounted elsewhere stay apart.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DiskId {
+    pub device: OsString,
+    pub mount_point: PathBuf,
+}
+
+impl DiskId {
+    pub fn of(disk: &Disk) -> Self {
+        Self {
+            device: disk.name().to_os_string(),
+            mount_point: disk.mount_point().to_path_buf(),
+        }
+    }
+}
+
+/// Cumulative counters of a disk since boot, read from the platform counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiskCounters {
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    /// Completed requests.
+    pub reads: u64,
+    pub writes: u64,
+    /// Time requests spent queued and being served, summed over all of them.
+    pub read_time_ms: u64,
+    pub write_time_ms: u64,
+    /// Time the device had at least one request in flight.
+    pub busy_time_ms: u64,
+    /// Requests in flight right now.
+    pub in_flight: u64,
+    /// Requests in flight integrated over time, `None` where only the current depth is known.
+    pub weighted_busy_time_ms: Option<u64>,
+}
+
+/// What iostat shows for one interval.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DiskIoStats {
+    pub read_kb_per_second: f32,
+    pub write_kb_per_second: f32,
+    pub read_iops: f32,
+    pub write_iops: f32,
+    /// Average time a request took, queueing included (iostat's await), 0 without requests.
+    pub await_ms: f32,
+    /// Average requests in flight (aqu-sz).
+    pub queue_depth: f32,
+    /// Share of the interval the device was busy, in percent.
+    pub utilization: f32,
+}
+
+impl DiskIoStats {
+    /// Rates over the `elapsed` time between two reads of the same disk's counters.
+    /// `None` when no time passed or the counters were reset in between, there is nothing to compare then.
+    pub fn between(last: &DiskCounters, current: &DiskCounters, elapsed: Duration) -> Option<Self> {
+        let elapsed_time = elapsed.as_secs_f64();
+        if elapsed_time <= 0.0 {
+            return None;
+        }
+        let elapsed_ms = elapsed_time * 1000.0;
+        let delta = |last: u64, current: u64| counter_delta(last, current).map(|x| x as f64);
+
+        let bytes_read = delta(last.bytes_read, current.bytes_read)?;
+        let bytes_written = delta(last.bytes_written, current.bytes_written)?;
+        let reads = delta(last.reads, current.reads)?;
+        let writes = delta(last.writes, current.writes)?;
+        let request_time = delta(last.read_time_ms, current.read_time_ms)? + delta(last.write_time_ms, current.write_time_ms)?;
+        let busy_time = delta(last.busy_time_ms, current.busy_time_ms)?;
+        let queue_depth = match (last.weighted_busy_time_ms, current.weighted_busy_time_ms) {
+            (Some(last), Some(current)) => delta(last, current)? / elapsed_ms,
+            _ => current.in_flight as f64,
+        };
+        Some(Self {
+            read_kb_per_second: (bytes_read / 1024.0 / elapsed_time) as f32,
+            write_kb_per_second: (bytes_written / 1024.0 / elapsed_time) as f32,
+            read_iops: (reads / elapsed_time) as f32,
+            write_iops: (writes / elapsed_time) as f32,
+            await_ms: if reads + writes > 0.0 { (request_time / (reads + writes)) as f32 } else { 0.0 },
+            queue_depth: queue_depth as f32,
+            utilization: (busy_time / elapsed_ms * 100.0).min(100.0) as f32,
+        })
+    }
+}
+
+/// Growth of a cumulative counter between two reads. A counter that went backwards from the upper half
+/// of the 32 bit range wrapped there (`unsigned long` on 32 bit kernels, the DWORD request counts on windows),
+/// anything else going backwards was reset, e.g. by a device re-created under the same name.
+pub fn counter_delta(last: u64, current: u64) -> Option<u64> {
+    const WRAP: u64 = 1 << 32;
+    match current.checked_sub(last) {
+        Some(delta) => Some(delta),
+        None if (WRAP / 2..WRAP).contains(&last) && current < WRAP => Some(WRAP - last + current),
+        None => None,
+    }
+}
+
+/// Space of a mounted filesystem, refreshed every `disk_space_interval_ms`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpaceUsage {
+    pub total: u64,
+    /// What unprivileged users can still write, root may have a reserve beyond it.
+    pub available: u64,
+    /// `None` where the filesystem or the platform has no fixed inode count.
+    pub inodes: Option<InodeUsage>,
+}
+
+impl SpaceUsage {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InodeUsage {
+    pub total: u64,
+    pub free: u64,
+}
+
+impl InodeUsage {
+    pub fn used_percent(&self) -> f32 {
+        match self.total {
+            0 => 0.0,
+            total => (total.saturating_sub(self.free) as f64 / total as f64 * 100.0) as f32,
+        }
+    }
+}
+
+/// Used space at one refresh, the growth rate is fitted through these.
+#[derive(Clone, Copy, Debug)]
+pub struct SpaceSample {
+    pub taken_at: Instant,
+    pub used: u64,
+}
+
+/// What a node of the block device tree is, as the kernel exposes it under `/sys/block`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub enum BlockDeviceKind {
+    Disk,
+    Partition,
+    Loop,
+    /// An LVM logical volume (device mapper with an `LVM-` uuid).
+    Lvm,
+    /// A dm-crypt / LUKS mapping (device mapper with a `CRYPT-` uuid).
+    Crypt,
+    /// Any other device mapper target.
+    DeviceMapper,
+    /// An md software RAID with its level, e.g. `raid1`.
+    Raid(String),
+}
+
+impl fmt::Display for BlockDeviceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockDeviceKind::Disk => write!(f, "disk"),
+            BlockDeviceKind::Partition => write!(f, "part"),
+            BlockDeviceKind::Loop => write!(f, "loop"),
+            BlockDeviceKind::Lvm => write!(f, "lvm"),
+            BlockDeviceKind::Crypt => write!(f, "crypt"),
+            BlockDeviceKind::DeviceMapper => write!(f, "dm"),
+            BlockDeviceKind::Raid(level) => write!(f, "{}", level),
+        }
+    }
+}
+
+/// One node of the block device tree: a disk, its partitions and whatever is stacked on them
+/// (LVM, dm-crypt, md RAID). A device stacked on several parents, like a RAID, shows up under each of them.
+#[derive(Clone, Debug)]
+pub struct BlockDevice {
+    /// Kernel name, e.g. `sda1` or `dm-0`.
+    pub name: String,
+    /// Device mapper name, e.g. `vg0-root`.
+    pub label: Option<String>,
+    pub kind: BlockDeviceKind,
+    pub size: u64,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    pub rotational: Option<bool>,
+    /// The active I/O scheduler, the one in brackets in `queue/scheduler`.
+    pub scheduler: Option<String>,
+    pub mount_points: Vec<PathBuf>,
+    /// Rates since the previous refresh, `None` on the first one.
+    pub io: Option<DiskIoStats>,
+    pub children: Vec<BlockDevice>,
+}
+
+/// Counters of a disk and when they were read, the rates of the next read are taken against it.
+#[derive(Clone, Copy, Debug)]
+pub struct DiskMeasurement {
+    pub taken_at: Instant,
+    pub counters: DiskCounters,
+}
+
+#[derive(Clone)]
+pub struct DiskInformations{
+    pub id: DiskId,
+    pub name: String,
+    pub mount_point: String,
+    pub space: SpaceUsage,
+    pub space_history: Data<SpaceSample>,
+    pub kind: String,
+    pub file_system: String,
+    pub is_removable: String,
+    pub last_measurement: Option<DiskMeasurement>,
+    pub io_data_points: Data<DiskIoStats>,
+    /// Largest read or write rate in the history, at least `plots.disk_y_min_bound`.
+    pub y_max_bound: f32,
+    pub removed_since: Option<Instant>,
+}
+
+impl DiskInformations {
+    /// Least squares slope of the used space over the history window, in bytes per second.
+    /// `None` until two samples some time apart are in.
+    pub fn growth_rate(&self) -> Option<f64> {
+        let first = self.space_history.data_iter().next()?.taken_at;
+        let points: Vec<(f64, f64)> = self.space_history.data_iter()
+            .map(|x| (x.taken_at.saturating_duration_since(first).as_secs_f64(), x.used as f64))
+            .collect();
+        let count = points.len() as f64;
+        let mean_time = points.iter().map(|x| x.0).sum::<f64>() / count;
+        let mean_used = points.iter().map(|x| x.1).sum::<f64>() / count;
+        let variance: f64 = points.iter().map(|x| (x.0 - mean_time).powi(2)).sum();
+        if points.len() < 2 || variance <= 0.0 {
+            return None;
+        }
+        let covariance: f64 = points.iter().map(|x| (x.0 - mean_time) * (x.1 - mean_used)).sum();
+        Some(covariance / variance)
+    }
+
+    /// When the available space runs out if it keeps growing like over the history window, `None` unless it grows.
+    pub fn time_to_full(&self) -> Option<Duration> {
+        let growth_rate = self.growth_rate().filter(|x| *x > 0.0)?;
+        Duration::try_from_secs_f64(self.space.available as f64 / growth_rate).ok()
+    }
+}
+
+#[derive(Clone)]
+pub struct NetworkInformations {
+    pub id: InterfaceId,
+    pub number: usize,
+    pub interface_name: String,
+    pub mac_address: MacAddr,
+    pub details: InterfaceDetails,
+    pub network_display: NetworkDisplay,
+    pub total_errors_on_received: u64,
+    pub total_errors_on_transmitted: u64,
+    /// Counted since the monitor first saw the interface.
+    pub totals: NetworkTotals,
+    /// Dropped packets in both directions as last read, only known on linux.
+    pub last_drops: Option<u64>,
+    pub removed_since: Option<Instant>,
+}
+
+/// Kernel interface index where the platform has one, the name otherwise. The index survives a rename and,
+/// unlike the MAC address, is unique: loopback, tun and many virtual interfaces share an all-zero MAC.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum InterfaceId {
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    Index(u32),
+    Name(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InterfaceAddress {
+    pub address: IpAddr,
+    pub prefix_length: u8,
+}
+
+impl fmt::Display for InterfaceAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_length)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub enum InterfaceKind {
+    Physical,
+    Loopback,
+    Bridge,
+    Veth,
+    Tun,
+    Virtual,
+    Unknown,
+}
+
+impl fmt::Display for InterfaceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterfaceKind::Physical => write!(f, "physical"),
+            InterfaceKind::Loopback => write!(f, "loopback"),
+            InterfaceKind::Bridge => write!(f, "bridge"),
+            InterfaceKind::Veth => write!(f, "veth"),
+            InterfaceKind::Tun => write!(f, "tun/tap"),
+            InterfaceKind::Virtual => write!(f, "virtual"),
+            InterfaceKind::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Addresses and link of an interface. Only read on linux, elsewhere everything stays unknown.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterfaceDetails {
+    pub addresses: Vec<InterfaceAddress>,
+    /// `up`, `down`, `dormant`... as the kernel names it.
+    pub operstate: Option<String>,
+    pub carrier: Option<bool>,
+    pub speed_mbps: Option<u64>,
+    pub duplex: Option<String>,
+    pub mtu: Option<u32>,
+    pub kind: InterfaceKind,
+}
+
+impl Default for InterfaceDetails {
+    fn default() -> Self {
+        Self {
+            addresses: Vec::new(),
+            operstate: None,
+            carrier: None,
+            speed_mbps: None,
+            duplex: None,
+            mtu: None,
+            kind: InterfaceKind::Unknown,
+        }
+    }
+}
+
+impl InterfaceDetails {
+    /// Share of the link speed used by `bytes_per_second` in one direction, `None` without a known speed.
+    pub fn utilization(&self, bytes_per_second: f64) -> Option<f64> {
+        let speed_bits = self.speed_mbps? as f64 * 1_000_000.0;
+        Some(bytes_per_second * 8.0 / speed_bits * 100.0)
+    }
+}
+
+/// Results of one reachability probe.
+#[derive(Clone)]
+pub struct ProbeInformations {
+    pub name: String,
+    pub kind: ProbeKind,
+    pub target: String,
+    /// Round trip of every attempt in milliseconds, `None` for a lost one.
+    pub round_trips: Data<Option<f32>>,
+    pub last_error: Option<String>,
+    pub sent: u64,
+    pub lost: u64,
+}
+
+impl ProbeInformations {
+    pub fn new(probe: &ProbeConfig, history_length: usize) -> Self {
+        Self {
+            name: probe.name().to_string(),
+            kind: probe.kind,
+            target: probe.target.clone(),
+            round_trips: Data::new(history_length),
+            last_error: None,
+            sent: 0,
+            lost: 0,
+        }
+    }
+
+    /// Share of the attempts in the history that got no answer, 0 before the first one.
+    pub fn loss_percent(&self) -> f32 {
+        let attempts = self.round_trips.data_iter().count();
+        if attempts == 0 {
+            return 0.0;
+        }
+        let lost = self.round_trips.data_iter().filter(|x| x.is_none()).count();
+        lost as f32 / attempts as f32 * 100.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NetworkTotals {
+    pub received: u64,
+    pub transmitted: u64,
+    pub packets_received: u64,
+    pub packets_transmitted: u64,
+    pub errors: u64,
+    pub drops: u64,
+}
+
+#[derive(Clone)]
+pub struct NetworkDisplay {
+    /// Bytes per second.
+    pub received_plot_points: Data<f64>,
+    pub transmitted_plot_points: Data<f64>,
+    /// Packets per second.
+    pub packets_received_plot_points: Data<f32>,
+    pub packets_transmitted_plot_points: Data<f32>,
+    /// Receive and transmit errors per second together.
+    pub errors_plot_points: Data<f32>,
+    pub drops_plot_points: Data<f32>,
+}
+
+impl NetworkDisplay {
+    pub fn new(history_length: usize) -> Self {
+        Self {
+            received_plot_points: Data::new(history_length),
+            transmitted_plot_points: Data::new(history_length),
+            packets_received_plot_points: Data::new(history_length),
+            packets_transmitted_plot_points: Data::new(history_length),
+            errors_plot_points: Data::new(history_length),
+            drops_plot_points: Data::new(history_length),
+        }
+    }
+
+    pub fn set_data_points(&mut self, history_length: usize) {
+        self.received_plot_points.set_data_points(history_length);
+        self.transmitted_plot_points.set_data_points(history_length);
+        self.packets_received_plot_points.set_data_points(history_length);
+        self.packets_transmitted_plot_points.set_data_points(history_length);
+        self.errors_plot_points.set_data_points(history_length);
+        self.drops_plot_points.set_data_points(history_length);
+    }
+}
+
+/// Where the memory went, in bytes. Only collected on linux.
+/// Page cache includes `shared` (tmpfs and shared memory), `dirty` and `writeback` are part of the page cache too.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryBreakdown {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+    pub buffers: u64,
+    pub cached: u64,
+    pub shared: u64,
+    pub slab_reclaimable: u64,
+    pub slab_unreclaimable: u64,
+    pub dirty: u64,
+    pub writeback: u64,
+    pub huge_pages_total: u64,
+    pub huge_pages_free: u64,
+}
+
+impl MemoryBreakdown {
+    /// Memory owned by processes, what's left after the kernel's caches, slab and huge pages.
+    pub fn applications(&self) -> u64 {
+        self.total
+            .saturating_sub(self.free)
+            .saturating_sub(self.buffers)
+            .saturating_sub(self.cached)
+            .saturating_sub(self.slab_reclaimable)
+            .saturating_sub(self.slab_unreclaimable)
+            .saturating_sub(self.huge_pages_total)
+    }
+
+    /// Bands in the order they are stacked on the plot, bottom first. Together with free they add up to total.
+    /// Everything above applications and unreclaimable slab can be given back under pressure.
+    pub fn stacked(&self) -> [(&'static str, u64); 7] {
+        [
+            ("applications", self.applications()),
+            ("huge pages", self.huge_pages_total),
+            ("slab unreclaimable", self.slab_unreclaimable),
+            ("slab reclaimable", self.slab_reclaimable),
+            ("buffers", self.buffers),
+            ("page cache", self.cached.saturating_sub(self.shared)),
+            ("shared", self.shared),
+        ]
+    }
+}
+
+/// Paging activity per second from `/proc/vmstat`. Only collected on linux.
+#[derive(Clone)]
+pub struct PagingInformations {
+    pub swap_in_per_second: Data<f32>,
+    pub swap_out_per_second: Data<f32>,
+    pub minor_faults_per_second: Data<f32>,
+    pub major_faults_per_second: Data<f32>,
+}
+
+impl PagingInformations {
+    pub fn new(history_length: usize) -> Self {
+        Self {
+            swap_in_per_second: Data::new(history_length),
+            swap_out_per_second: Data::new(history_length),
+            minor_faults_per_second: Data::new(history_length),
+            major_faults_per_second: Data::new(history_length),
+        }
+    }
+
+    pub fn set_data_points(&mut self, history_length: usize) {
+        self.swap_in_per_second.set_data_points(history_length);
+        self.swap_out_per_second.set_data_points(history_length);
+        self.minor_faults_per_second.set_data_points(history_length);
+        self.major_faults_per_second.set_data_points(history_length);
+    }
+
+    /// Pages are both read back from swap and written out to it faster than the threshold.
+    /// Swapping out alone is the kernel making room, it's the read back that stalls processes.
+    pub fn is_thrashing(&self, thresholds: &ThresholdsConfig) -> bool {
+        let swap_in = self.swap_in_per_second.last().copied().unwrap_or(0.0);
+        let swap_out = self.swap_out_per_second.last().copied().unwrap_or(0.0);
+        swap_in > 0.0 && swap_in + swap_out >= thresholds.swap_pages_per_second
+    }
+}
+
+/// One line of a `/proc/pressure` file. The averages are percentages of wall time,
+/// `total_us` is the stall time since boot in microseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PressureLine {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    pub total_us: u64,
+}
+
+/// `some`: at least one task was stalled on the resource. `full`: every non idle task was.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pressure {
+    pub some: PressureLine,
+    pub full: Option<PressureLine>,
+}
+
+/// Pressure stall information of one resource. `latest` is `None` without PSI (windows, or a linux
+/// kernel without it). The plots hold the share of time stalled since the previous refresh, from the totals.
+#[derive(Clone)]
+pub struct PressureInformations {
+    pub latest: Option<Pressure>,
+    pub some_plot_points: Data<f32>,
+    pub full_plot_points: Data<f32>,
+}
+
+impl PressureInformations {
+    pub fn new(history_length: usize) -> Self {
+        Self {
+            latest: None,
+            some_plot_points: Data::new(history_length),
+            full_plot_points: Data::new(history_length),
+        }
+    }
+
+    pub fn set_data_points(&mut self, history_length: usize) {
+        self.some_plot_points.set_data_points(history_length);
+        self.full_plot_points.set_data_points(history_length);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+    Power,
+}
+
+impl SensorKind {
+    pub const ALL: [SensorKind; 3] = [SensorKind::Temperature, SensorKind::Fan, SensorKind::Power];
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Power => "W",
+        }
+    }
+}
+
+impl fmt::Display for SensorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SensorKind::Temperature => write!(f, "temperatures"),
+            SensorKind::Fan => write!(f, "fans"),
+            SensorKind::Power => write!(f, "power"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SensorId {
+    pub kind: SensorKind,
+    pub label: String,
+}
+
+/// One reading as the collectors hand it over, before it's merged into the sensor's history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SensorReading {
+    pub id: SensorId,
+    pub current: f32,
+    pub max: Option<f32>,
+    pub critical: Option<f32>,
+}
+
+#[derive(Clone)]
+pub struct SensorInformations {
+    pub id: SensorId,
+    pub current: f32,
+    pub max: Option<f32>,
+    pub critical: Option<f32>,
+    pub plot_points: Data<f32>,
+    pub removed_since: Option<Instant>,
+}
+
+impl SensorInformations {
+    pub fn new(reading: &SensorReading, history_length: usize) -> Self {
+        Self {
+            id: reading.id.clone(),
+            current: reading.current,
+            max: reading.max,
+            critical: reading.critical,
+            plot_points: Data::new(history_length),
+            removed_since: None,
+        }
+    }
+
+    /// Whether the current reading is past the configured threshold or the sensor's own critical value.
+    pub fn is_alerting(&self, thresholds: &ThresholdsConfig) -> bool {
+        if self.removed_since.is_some() {
+            return false;
+        }
+        match self.id.kind {
+            SensorKind::Temperature => {
+                self.current >= thresholds.temperature_celsius
+                    || self.critical.map_or(false, |critical| self.current >= critical)
+            }
+            SensorKind::Fan => thresholds.fan_min_rpm.map_or(false, |min| self.current < min),
+            SensorKind::Power => thresholds.power_watts.map_or(false, |max| self.current >= max),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ProcessInformations {
+    pub pid: Pid,
+    pub name: String,
+    pub cpu: f32,
+    pub memory: f32,
+    /// Bytes read and written per second, 0 on the first refresh.
+    pub disk: f32,
+    /// 0 where the platform doesn't report it.
+    pub major_faults_per_second: f32,
+    /// tcp bytes sent and received per second, 0 where the platform doesn't report it.
+    pub network: f32,
+    pub child_processes: Vec<SecificProcess>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "tcp"),
+            Protocol::Udp => write!(f, "udp"),
+        }
+    }
+}
+
+/// One tcp or udp socket, ipv4 or ipv6.
+#[derive(Clone, Debug)]
+pub struct ConnectionInformations {
+    pub protocol: Protocol,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: &'static str,
+    /// `None` when the socket belongs to a process we can't read.
+    pub pid: Option<Pid>,
+    pub process_name: String,
+    pub send_queue: u32,
+    pub receive_queue: u32,
+}
+
+#[derive(Clone)]
+pub struct ProcessDetails {
+    pub pid: Pid,
+    pub name: String,
+    /// The error says why it couldn't be read, usually another user's process.
+    pub memory: Result<ProcessMemory, String>,
+    pub open_files: Result<OpenFiles, String>,
+    /// Busiest thread first.
+    pub threads: Result<Vec<ThreadInformations>, String>,
+    /// tcp bytes per second since the details were opened.
+    pub network_plot_points: Data<f32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ThreadInformations {
+    pub tid: Pid,
+    pub name: String,
+    /// Single letter of `/proc/<pid>/task/<tid>/stat`, R running, S sleeping, D disk sleep...
+    pub state: char,
+    /// Percent of one core since the previous refresh, 0 the first time the thread is seen.
+    pub cpu: f32,
+    pub last_cpu: u32,
+    pub voluntary_switches: u64,
+    pub involuntary_switches: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct OpenFiles {
+    pub entries: Vec<OpenFile>,
+    /// Soft RLIMIT_NOFILE, `None` when unlimited.
+    pub limit: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OpenFileKind {
+    File,
+    Pipe,
+    Socket,
+    AnonInode,
+    Other,
+}
+
+impl fmt::Display for OpenFileKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenFileKind::File => write!(f, "file"),
+            OpenFileKind::Pipe => write!(f, "pipe"),
+            OpenFileKind::Socket => write!(f, "socket"),
+            OpenFileKind::AnonInode => write!(f, "anon inode"),
+            OpenFileKind::Other => write!(f, "other"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpenFile {
+    pub fd: u32,
+    pub kind: OpenFileKind,
+    /// Where the descriptor points, a path or `socket:[inode]`.
+    pub target: String,
+    /// Socket endpoints or the anon inode type, empty for files and pipes.
+    pub description: String,
+}
+
+/// The I/O scheduling class of `ioprio_set(2)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub enum IoPriorityClass {
+    /// Never set, the kernel derives a best effort level from the nice value.
+    None,
+    RealTime,
+    BestEffort,
+    Idle,
+}
+
+impl IoPriorityClass {
+    pub const ALL: [IoPriorityClass; 4] = [IoPriorityClass::None, IoPriorityClass::RealTime, IoPriorityClass::BestEffort, IoPriorityClass::Idle];
+
+    /// Only real time and best effort have levels.
+    pub fn has_levels(&self) -> bool {
+        matches!(self, IoPriorityClass::RealTime | IoPriorityClass::BestEffort)
+    }
+}
+
+impl fmt::Display for IoPriorityClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoPriorityClass::None => write!(f, "none"),
+            IoPriorityClass::RealTime => write!(f, "rt"),
+            IoPriorityClass::BestEffort => write!(f, "be"),
+            IoPriorityClass::Idle => write!(f, "idle"),
+        }
+    }
+}
+
+/// Class and level (0 is the highest, 7 the lowest) as `ionice` shows them, e.g. `be/4`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IoPriority {
+    pub class: IoPriorityClass,
+    pub level: u8,
+}
+
+impl fmt::Display for IoPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.class.has_levels() {
+            write!(f, "{}/{}", self.class, self.level)
+        } else {
+            write!(f, "{}", self.class)
+        }
+    }
+}
+
+/// One process of the I/O view, rates from `/proc/<pid>/io` since the previous refresh.
+#[derive(Clone, Debug)]
+pub struct ProcessIo {
+    pub pid: Pid,
+    pub name: String,
+    /// Bytes fetched from or sent to the storage layer, page cache hits don't count.
+    pub read_bytes_per_second: f32,
+    pub write_bytes_per_second: f32,
+    /// Dirty page cache the process truncated before it was written back, so it never reached the disk.
+    pub cancelled_write_bytes_per_second: f32,
+    /// read(2)/write(2)-like calls, whether they hit the disk or not.
+    pub read_syscalls_per_second: f32,
+    pub write_syscalls_per_second: f32,
+    /// `None` when the process can't be asked.
+    pub priority: Option<IoPriority>,
+}
+
+impl ProcessIo {
+    pub fn bytes_per_second(&self) -> f32 {
+        self.read_bytes_per_second + self.write_bytes_per_second
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OpenFileSearch {
+    pub query: String,
+    pub matches: Result<Vec<OpenFileMatch>, String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OpenFileMatch {
+    pub pid: Pid,
+    pub process_name: String,
+    pub file: OpenFile,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProcessMemory {
+    pub total: MemoryCounters,
+    pub mapping_groups: Vec<MappingGroup>,
+}
+
+/// Memory of a process or of some of its mappings, in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryCounters {
+    pub rss: u64,
+    /// Shared pages split evenly between the processes mapping them.
+    pub pss: u64,
+    pub shared_clean: u64,
+    pub shared_dirty: u64,
+    pub private_clean: u64,
+    pub private_dirty: u64,
+    pub swap: u64,
+}
+
+impl MemoryCounters {
+    /// What would be freed if the process exited.
+    pub fn uss(&self) -> u64 {
+        self.private_clean + self.private_dirty
+    }
+}
+
+/// All mappings backed by the same file, or the same kind of anonymous memory (`[heap]`, `[stack]`, `[anon]`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MappingGroup {
+    pub name: String,
+    pub mappings: usize,
+    /// Virtual size.
+    pub size: u64,
+    pub counters: MemoryCounters,
+    /// Change of resident memory since the details window was opened.
+    pub rss_growth: i64,
+}
+
+#[derive(Clone)]
+pub struct SecificProcess {
+    pub pid: Pid,
+    pub name: String,
+    pub cpu: f32,
+    pub memory: f32,
+    pub disk: f32,
+    pub major_faults_per_second: f32,
+    pub network: f32,
+}
+
+impl Device for CpuData {
+    type Id = String;
+
+    fn id(&self) -> &String {
+        &self.name
+    }
+
+    fn removed_since(&self) -> Option<Instant> {
+        self.removed_since
+    }
+
+    fn set_removed_since(&mut self, removed_since: Option<Instant>) {
+        self.removed_since = removed_since;
+    }
+}
+
+impl Device for DiskInformations {
+    type Id = DiskId;
+
+    fn id(&self) -> &DiskId {
+        &self.id
+    }
+
+    fn removed_since(&self) -> Option<Instant> {
+        self.removed_since
+    }
+
+    fn set_removed_since(&mut self, removed_since: Option<Instant>) {
+        self.removed_since = removed_since;
+    }
+}
+
+impl Device for SensorInformations {
+    type Id = SensorId;
+
+    fn id(&self) -> &SensorId {
+        &self.id
+    }
+
+    fn removed_since(&self) -> Option<Instant> {
+        self.removed_since
+    }
+
+    fn set_removed_since(&mut self, removed_since: Option<Instant>) {
+        self.removed_since = removed_since;
+    }
+}
+
+impl Device for NetworkInformations {
+    type Id = InterfaceId;
+
+    fn id(&self) -> &InterfaceId {
+        &self.id
+    }
+
+    fn removed_since(&self) -> Option<Instant> {
+        self.removed_since
+    }
+
+    fn set_removed_since(&mut self, removed_since: Option<Instant>) {
+        self.removed_since = removed_since;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_history_keeps_its_newest_records_across_chunks() {
+        let mut history = Data::new(40);
+        (0..100).for_each(|x| history.push(x));
+
+        assert_eq!(history.data_iter().copied().collect::<Vec<i32>>(), (59..100).collect::<Vec<i32>>());
+        assert_eq!(history.last(), Some(&99));
+        assert!(history.chunks.len() <= 2);
+
+        history.set_data_points(2);
+        assert_eq!(history.data_iter().copied().collect::<Vec<i32>>(), [97, 98, 99]);
+
+        let mut short = Data::new(1);
+        (0..5).for_each(|x| short.push(x));
+        assert_eq!(short.data_iter().copied().collect::<Vec<i32>>(), [3, 4]);
+    }
+
+    #[test]
+    fn a_published_history_shares_its_full_chunks() {
+        let mut history = Data::new(100);
+        (0..DATA_CHUNK_LENGTH + 3).for_each(|x| history.push(x));
+        let published = history.clone();
+
+        assert!(Arc::ptr_eq(&history.chunks[0], &published.chunks[0]));
+
+        history.push(1000);
+
+        assert_eq!(published.data_iter().count(), DATA_CHUNK_LENGTH + 3);
+        assert_eq!(published.last(), Some(&(DATA_CHUNK_LENGTH + 2)));
+        assert_eq!(history.last(), Some(&1000));
+    }
+}
@@ -0,0 +1,81 @@
+use std::ffi::CString;
+use std::ptr;
+use winapi::um::ioapiset::DeviceIoControl;
+use winapi::um::winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE};
+use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+use winapi::um::fileapi::{CreateFileA, OPEN_EXISTING};
+use winapi::um::winioctl::{IOCTL_DISK_PERFORMANCE, DISK_PERFORMANCE};
+use winapi::shared::minwindef::{DWORD, FALSE};
+use winapi::shared::ntdef::HANDLE;
+use crate::error::MonitorError;
+use crate::snapshot::{DiskCounters, DiskId};
+
+/// The times of DISK_PERFORMANCE count in 100 ns.
+const TICKS_PER_MS: u64 = 10_000;
+
+/// Counters of the volume mounted at `disk.mount_point`, e.g. `C:\`.
+pub fn disk_counters(disk: &DiskId) -> Result<DiskCounters, MonitorError> {
+    let disk_name = disk.mount_point.display().to_string().replace('\\', "");
+    let performance = get_disk_performance(disk_name.trim())?;
+    unsafe {
+        let ticks = |value: &winapi::shared::ntdef::LARGE_INTEGER| *value.QuadPart() as u64;
+        // the volume is busy whenever it isn't idle, QueryTime is the clock of the sample
+        let busy = ticks(&performance.QueryTime).saturating_sub(ticks(&performance.IdleTime));
+        Ok(DiskCounters {
+            bytes_read: ticks(&performance.BytesRead),
+            bytes_written: ticks(&performance.BytesWritten),
+            reads: u64::from(performance.ReadCount),
+            writes: u64::from(performance.WriteCount),
+            read_time_ms: ticks(&performance.ReadTime) / TICKS_PER_MS,
+            write_time_ms: ticks(&performance.WriteTime) / TICKS_PER_MS,
+            busy_time_ms: busy / TICKS_PER_MS,
+            in_flight: u64::from(performance.QueueDepth),
+            weighted_busy_time_ms: None,
+        })
+    }
+}
+
+// FROM C++ https://stackoverflow.com/a/30451751 MY CREATIVE INVERTION AND TRIAL AND ERROR METHOD (It works) and winapi docs and frineds help
+fn get_disk_performance(disk_name: &str) -> Result<DISK_PERFORMANCE, MonitorError> {
+    let device_path = CString::new(format!("\\\\.\\{}", disk_name))
+        .map_err(|_| MonitorError::DiskOpenFailed { disk: disk_name.to_string() })?;
+    unsafe {
+        let dev = CreateFileA(
+            device_path.as_ptr(),
+            winapi::um::winnt::FILE_READ_ATTRIBUTES,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            ptr::null_mut(),
+            OPEN_EXISTING,
+            0,
+            ptr::null_mut(),
+        );
+
+        if HANDLE::is_null(dev) || dev == INVALID_HANDLE_VALUE {
+            return Err(MonitorError::DiskOpenFailed { disk: disk_name.to_string() });
+        }
+
+        let mut disk_info: DISK_PERFORMANCE = std::mem::zeroed();
+        let mut bytes: DWORD = 0;
+
+        //https://learn.microsoft.com/en-us/windows/win32/fileio/disk-management-control-codes
+        //https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/System/Ioctl/struct.DISK_PERFORMANCE.html
+        if DeviceIoControl(
+            dev,
+            IOCTL_DISK_PERFORMANCE,
+            ptr::null_mut(),
+            0,
+            &mut disk_info as *mut _ as *mut winapi::ctypes::c_void,
+            std::mem::size_of::<DISK_PERFORMANCE>() as DWORD,
+            &mut bytes,
+            ptr::null_mut(),
+        ) == FALSE
+        {
+            CloseHandle(dev);
+            return Err(MonitorError::DiskPerformanceUnavailable { disk: disk_name.to_string() });
+        }
+
+        CloseHandle(dev);
+
+        Ok(disk_info)
+    }
+}